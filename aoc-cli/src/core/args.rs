@@ -1,4 +1,6 @@
-use clap::{Parser, Subcommand};
+use crate::{isolate::ProcessLimits, report::OutputFormat};
+use clap::{ArgGroup, Parser, Subcommand};
+use std::{path::PathBuf, time::Duration};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -27,9 +29,186 @@ pub enum Command {
         year: Option<i32>,
         /// List of days to solve. Defaults to [all implemented days].
         days: Vec<u32>,
+        /// Read the input from the given file, from a directory laid out as aoc-lib/input,
+        /// or from the standard input with '-'. Defaults to aoc-lib/input, then the embedded inputs.
+        #[arg(short, long)]
+        input: Option<String>,
+        /// Time limit for each part in seconds. Parts exceeding it are stopped at their next progress update.
+        #[arg(short, long, value_parser = parse_seconds)]
+        time_limit: Option<Duration>,
+        /// Solve the given variant of each day instead of the default one.
+        #[arg(long, conflicts_with = "all_variants")]
+        variant: Option<String>,
+        /// Solve every variant of each day and compare their answers and timings.
+        #[arg(long)]
+        all_variants: bool,
+        /// Solve with the input of the given profile, stored as aoc-lib/input/yearXXXX/dayXX/<profile>.txt.
+        #[arg(long, conflicts_with = "all_profiles")]
+        profile: Option<String>,
+        /// Solve with the input of every profile and flag the profiles with answers differing from their confirmed ones.
+        #[arg(long, conflicts_with = "all_variants")]
+        all_profiles: bool,
+        /// Replay the intermediate states of solutions supporting visualization.
+        #[arg(long)]
+        animate: bool,
+        /// Show log messages of the solutions. Repeat for more detail: -v info, -vv debug, -vvv trace.
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+        /// Number of days solved concurrently. With more than one job the progress of each day
+        /// is shown on its own row, without animations.
+        #[arg(short, long, default_value_t = 1, conflicts_with = "all_variants")]
+        jobs: usize,
+        /// Print the results of all days at the end in the given format instead of the progress.
+        #[arg(
+            short,
+            long,
+            value_enum,
+            default_value_t,
+            conflicts_with = "all_variants"
+        )]
+        format: OutputFormat,
+        #[command(flatten)]
+        isolation: IsolationArgs,
+        /// Record the progress events of the run to the given file, to be replayed by 'ui --replay'.
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["all_variants", "all_profiles"])]
+        record: Option<PathBuf>,
+    },
+    /// Run solutions repeatedly and report their runtime statistics
+    Bench {
+        /// Specifies the target year. Defaults to the latest available AOC year.
+        #[arg(short, long)]
+        year: Option<i32>,
+        /// List of days to benchmark. Defaults to [all implemented days].
+        days: Vec<u32>,
+        /// Number of times each day is run.
+        #[arg(short, long, default_value_t = 10)]
+        runs: usize,
+        /// Benchmark the given variant of each day instead of the default one.
+        #[arg(long)]
+        variant: Option<String>,
+        /// Save the results as the baseline for later comparisons.
+        #[arg(long)]
+        save_baseline: bool,
+        /// Compare the results with the saved baseline.
+        #[arg(short, long)]
+        compare: bool,
+        /// Fail if the median runtime of a day exceeds the baseline by more than this percentage.
+        #[arg(long, default_value_t = 10.0, requires = "compare")]
+        threshold: f64,
+    },
+    /// Rebuild, run the example tests and solve the input of a day whenever its files change
+    Watch {
+        /// Specifies the target year. Defaults to the latest available AOC year.
+        #[arg(short, long)]
+        year: Option<i32>,
+        /// Day to watch.
+        day: u32,
+        /// Solve the input in this process with aoc-lib built as a dynamic library, reloading it after each build.
+        #[arg(long)]
+        hot: bool,
     },
+    /// Solve puzzles requested by editors and other programs
+    #[command(group(ArgGroup::new("transport").required(true)))]
+    Serve {
+        /// Read JSON requests from the standard input and write the progress events to the standard output, one per line.
+        #[arg(long, group = "transport")]
+        stdio: bool,
+        /// Serve a REST API streaming the progress events, and the web UI built by 'npm run build', on the given local port.
+        #[arg(long, group = "transport", value_name = "PORT")]
+        http: Option<u16>,
+        /// Directory of the built web UI served over HTTP.
+        #[arg(long, default_value = crate::http::DEFAULT_WEB_DIR, requires = "http")]
+        web_dir: PathBuf,
+        /// Time limit for each part in seconds. Parts exceeding it are stopped at their next progress update.
+        #[arg(short, long, value_parser = parse_seconds)]
+        time_limit: Option<Duration>,
+        /// Send log messages of the solutions. Repeat for more detail: -v info, -vv debug, -vvv trace.
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+        #[command(flatten)]
+        isolation: IsolationArgs,
+    },
+    /// Solve a single day read from the standard input for an isolated run
+    #[command(hide = true)]
+    Worker,
     /// Display the pretty UI with snowing effects.
-    Ui,
+    Ui {
+        /// Record the progress events of each run to the given file, replacing the previous run.
+        #[arg(long, value_name = "FILE")]
+        record: Option<PathBuf>,
+        /// Play back a recorded run instead of solving the days, e.g. as a demo.
+        #[arg(long, value_name = "FILE", conflicts_with = "record")]
+        replay: Option<PathBuf>,
+        /// Playback speed of the recording relative to the original run.
+        #[arg(long, default_value_t = 1.0, requires = "replay")]
+        speed: f32,
+        /// Solve with the aoc-lib library built by 'watch --hot', reloading it whenever it is rebuilt.
+        #[arg(long, conflicts_with_all = ["record", "replay"])]
+        hot_reload: bool,
+    },
     /// Generate 3D height map from day 12 input.
     Day12Extra,
 }
+
+/// Options for solving each day in its own process.
+#[derive(clap::Args)]
+pub struct IsolationArgs {
+    /// Solve each day in a separate process, so that a crashing day does not stop the others.
+    #[arg(long)]
+    pub isolate: bool,
+    /// Memory limit of each process in megabytes. Only supported on Unix.
+    #[arg(long, value_name = "MB", requires = "isolate")]
+    pub memory_limit: Option<u64>,
+    /// Stack size of the solutions in megabytes.
+    #[arg(long, value_name = "MB", requires = "isolate")]
+    pub stack_size: Option<usize>,
+    /// Time limit for each day in seconds, after which its process is killed.
+    #[arg(long, value_name = "SECONDS", requires = "isolate", value_parser = parse_seconds)]
+    pub wall_time_limit: Option<Duration>,
+}
+impl IsolationArgs {
+    /// Returns the limits of the processes, or None if the days are not isolated.
+    pub fn limits(&self) -> Option<ProcessLimits> {
+        const MB: u64 = 1024 * 1024;
+        self.isolate.then(|| ProcessLimits {
+            memory_limit: self.memory_limit.map(|mb| mb * MB),
+            stack_size: self.stack_size.map(|mb| mb * MB as usize),
+            wall_time_limit: self.wall_time_limit,
+        })
+    }
+}
+
+/// Parses a positive number of seconds.
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    let seconds = value.parse::<f32>().map_err(|e| e.to_string())?;
+    match seconds > 0.0 {
+        true => Duration::try_from_secs_f32(seconds).map_err(|e| e.to_string()),
+        false => Err("should be a positive number of seconds".to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seconds_should_be_positive_and_finite() {
+        assert_eq!(parse_seconds("1.5"), Ok(Duration::from_millis(1500)));
+        for value in ["0", "-1", "NaN", "inf", "1e40", "", "1s"] {
+            assert!(parse_seconds(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn time_limits_are_parsed_as_durations() {
+        let args = Args::try_parse_from(["aoc", "solve", "-t", "0.25"]).unwrap();
+        assert!(
+            matches!(args.mode, Some(Command::Solve { time_limit: Some(limit), .. }) if limit == Duration::from_millis(250))
+        );
+        assert!(Args::try_parse_from(["aoc", "solve", "--time-limit", "-1"]).is_err());
+        assert!(Args::try_parse_from(["aoc", "serve", "--stdio", "-t", "-1"]).is_err());
+        assert!(
+            Args::try_parse_from(["aoc", "solve", "--isolate", "--wall-time-limit", "-1"]).is_err()
+        );
+    }
+}
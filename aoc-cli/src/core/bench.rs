@@ -0,0 +1,360 @@
+use aoc::{core::solution_runner::*, solutions, util::*};
+use chrono::Local;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+pub const DEFAULT_HISTORY_PATH: &str = "aoc_bench_history.json";
+pub const DEFAULT_BASELINE_PATH: &str = "aoc_bench_baseline.json";
+
+/// Distribution of the durations measured over the runs.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+impl Stats {
+    fn from_samples(samples: &[Duration]) -> Stats {
+        let sorted = samples.iter().copied().sorted().collect_vec();
+        // Nearest-rank percentile
+        let percentile = |p: f64| {
+            let rank = ((p * sorted.len() as f64).ceil() as usize).max(1);
+            sorted.get(rank - 1).copied().unwrap_or_default()
+        };
+        Stats {
+            min: sorted.first().copied().unwrap_or_default(),
+            median: percentile(0.5),
+            p95: percentile(0.95),
+        }
+    }
+}
+
+/// Benchmark results of a single day.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DayBench {
+    pub year_day: YearDay,
+    pub title: String,
+    pub variant: String,
+    pub runs: usize,
+    pub init: Stats,
+    pub parts: Vec<Stats>,
+    pub total: Stats,
+}
+
+/// Benchmark results of the days run together, as stored in the history and the baseline.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BenchRun {
+    pub timestamp: String,
+    pub days: Vec<DayBench>,
+}
+impl BenchRun {
+    pub fn load(path: &Path) -> GenericResult<BenchRun> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> GenericResult {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Returns the results of the same day and variant.
+    fn find(&self, day: &DayBench) -> Option<&DayBench> {
+        self.days
+            .iter()
+            .find(|d| d.year_day == day.year_day && d.variant == day.variant)
+    }
+
+    /// Appends the run to the history file, creating it if it does not exist yet.
+    pub fn append_to_history(&self, path: &Path) -> GenericResult {
+        let mut history: Vec<BenchRun> = match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => Err(err)?,
+        };
+        history.push(self.clone());
+        fs::write(path, serde_json::to_string_pretty(&history)?)?;
+        Ok(())
+    }
+}
+
+/// Runs each day the given number of times, printing the statistics of each day next to its baseline if given.
+/// Days failing in any run are reported and left out of the results.
+pub fn run_bench(
+    days: &[YearDay],
+    runs: usize,
+    options: &SolveOptions,
+    baseline: Option<&BenchRun>,
+) -> (BenchRun, Vec<YearDay>) {
+    let mut results = Vec::new();
+    let mut failed_days = Vec::new();
+    for &year_day in days {
+        match bench_day(year_day, runs, options) {
+            Ok(day) => {
+                let base = baseline.and_then(|b| b.find(&day));
+                if baseline.is_some() && base.is_none() {
+                    println!("\nDay {} is not found in the baseline", year_day.day);
+                }
+                print_day(&day, base);
+                results.push(day);
+            }
+            Err(err) => {
+                println!("\nDay {} - Error: {}", year_day.day, err);
+                failed_days.push(year_day);
+            }
+        }
+        if options.cancellation_token.is_cancelled() {
+            break;
+        }
+    }
+
+    let run = BenchRun {
+        timestamp: Local::now().to_rfc3339(),
+        days: results,
+    };
+    (run, failed_days)
+}
+
+fn bench_day(year_day: YearDay, runs: usize, options: &SolveOptions) -> GenericResult<DayBench> {
+    let variant = solutions::create_map()
+        .get(&year_day)
+        .and_then(|s| select_variant(s, options.variant.as_deref()))
+        .map(|s| s.info.variant.clone())
+        .ok_or("solution cannot be found")?;
+
+    let mut title = String::new();
+    let mut init = Vec::new();
+    let mut parts = [Vec::new(), Vec::new()];
+    let mut total = Vec::new();
+    for _ in 0..runs {
+        let solver = ThreadSolutionRunner {
+            options: options.clone(),
+        };
+        let stream: Arc<Mutex<ChannelSyncStream>> = solver.run(year_day, Input::Default);
+        let mut rx = stream
+            .lock()
+            .unwrap()
+            .take_receiver()
+            .ok_or("receiver should be available")?;
+        while let Some(progress) = rx.recv() {
+            match progress {
+                SolveProgress::Start(_, t) => title = t,
+                SolveProgress::Initialized(p) => init.push(p.duration),
+                SolveProgress::SuccessResult(p) => {
+                    parts[p.part.unwrap() as usize - 1].push(p.duration)
+                }
+                SolveProgress::Error(err) => Err(err)?,
+                SolveProgress::ErrorResult(p) => {
+                    Err(format!("part {} failed: {}", p.part.unwrap(), p.value))?
+                }
+                SolveProgress::Timeout(_) => Err("time limit exceeded")?,
+                SolveProgress::Cancelled(_) => Err("cancelled")?,
+                SolveProgress::Done(p) => total.push(p.duration),
+                _ => (),
+            }
+        }
+    }
+
+    Ok(DayBench {
+        year_day,
+        title,
+        variant,
+        runs,
+        init: Stats::from_samples(&init),
+        parts: parts.iter().map(|p| Stats::from_samples(p)).collect_vec(),
+        total: Stats::from_samples(&total),
+    })
+}
+
+/// Returns the days with a total median runtime exceeding the baseline by more than the threshold percentage.
+pub fn regressions(run: &BenchRun, baseline: &BenchRun, threshold: f64) -> Vec<YearDay> {
+    run.days
+        .iter()
+        .filter(|day| match baseline.find(day) {
+            Some(base) => change(&day.total, &base.total) > threshold,
+            None => false,
+        })
+        .map(|day| day.year_day)
+        .collect_vec()
+}
+
+/// Relative change of the median in percent.
+fn change(current: &Stats, base: &Stats) -> f64 {
+    let base = base.median.as_secs_f64();
+    match base > 0.0 {
+        true => (current.median.as_secs_f64() - base) / base * 100.0,
+        false => 0.0,
+    }
+}
+
+fn print_day(day: &DayBench, base: Option<&DayBench>) {
+    let mut header = vec!["", "Min", "Median", "P95"];
+    if base.is_some() {
+        header.extend(["Baseline", "Change"]);
+    }
+    let header = header.into_iter().map(|h| h.to_owned()).collect_vec();
+    // Rows are compared in the same order, as every day has an init, the parts and a total
+    let base_stats = base.map(|b| {
+        [&b.init]
+            .into_iter()
+            .chain(b.parts.iter())
+            .chain([&b.total])
+            .collect_vec()
+    });
+
+    let rows = [("Init".to_owned(), &day.init)]
+        .into_iter()
+        .chain(
+            day.parts
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (format!("Part {}", i + 1), p)),
+        )
+        .chain([("Total".to_owned(), &day.total)])
+        .enumerate()
+        .map(|(i, (name, stats))| {
+            let mut row = vec![
+                name,
+                fmt_duration(&stats.min),
+                fmt_duration(&stats.median),
+                fmt_duration(&stats.p95),
+            ];
+            if let Some(base_stats) = &base_stats {
+                match base_stats.get(i) {
+                    Some(base) => row.extend([
+                        fmt_duration(&base.median),
+                        format!("{:+.1}%", change(stats, base)),
+                    ]),
+                    None => row.extend(["-".to_owned(), "-".to_owned()]),
+                }
+            }
+            row
+        })
+        .collect_vec();
+
+    let widths = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|r| r[i].chars().count())
+                .chain([header[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect_vec();
+    let format_row = |row: &Vec<String>| {
+        row.iter()
+            .zip(widths.iter())
+            .map(|(cell, &width)| format!("{: <width$}", cell, width = width))
+            .join(" | ")
+            .trim_end()
+            .to_owned()
+    };
+
+    match day.variant.as_str() {
+        aoc::solution::DEFAULT_VARIANT => println!(
+            "\nDay {} - {} ({} runs)",
+            day.year_day.day, day.title, day.runs
+        ),
+        variant => println!(
+            "\nDay {} - {} ({}, {} runs)",
+            day.year_day.day, day.title, variant, day.runs
+        ),
+    }
+    println!("{}", format_row(&header));
+    println!("{}", widths.iter().map(|&w| "-".repeat(w)).join("-|-"));
+    rows.iter().for_each(|row| println!("{}", format_row(row)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&v| Duration::from_millis(v)).collect()
+    }
+
+    fn stats(median_millis: u64) -> Stats {
+        Stats {
+            median: Duration::from_millis(median_millis),
+            ..Default::default()
+        }
+    }
+
+    fn day_bench(day: u32, variant: &str, median_millis: u64) -> DayBench {
+        DayBench {
+            year_day: YearDay::new(2022, day),
+            title: String::new(),
+            variant: variant.to_owned(),
+            runs: 1,
+            init: Default::default(),
+            parts: Vec::new(),
+            total: stats(median_millis),
+        }
+    }
+
+    fn bench_run(days: Vec<DayBench>) -> BenchRun {
+        BenchRun {
+            timestamp: String::new(),
+            days,
+        }
+    }
+
+    #[test]
+    fn stats_of_a_single_sample() {
+        let stats = Stats::from_samples(&millis(&[7]));
+        assert_eq!(stats.min, Duration::from_millis(7));
+        assert_eq!(stats.median, Duration::from_millis(7));
+        assert_eq!(stats.p95, Duration::from_millis(7));
+    }
+
+    #[test]
+    fn stats_use_nearest_rank_percentiles() {
+        let stats = Stats::from_samples(&millis(&[10, 3, 8, 1, 5, 9, 2, 7, 4, 6]));
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(5));
+        assert_eq!(stats.p95, Duration::from_millis(10));
+    }
+
+    #[test]
+    fn stats_without_samples_are_zero() {
+        let stats = Stats::from_samples(&[]);
+        assert_eq!(stats.min, Duration::ZERO);
+        assert_eq!(stats.median, Duration::ZERO);
+        assert_eq!(stats.p95, Duration::ZERO);
+    }
+
+    #[test]
+    fn change_is_relative_to_the_baseline_median() {
+        assert_eq!(change(&stats(1500), &stats(1000)), 50.0);
+        assert_eq!(change(&stats(500), &stats(1000)), -50.0);
+        assert_eq!(change(&stats(1500), &stats(0)), 0.0);
+    }
+
+    #[test]
+    fn regressions_exceed_the_threshold() {
+        let baseline = bench_run(vec![
+            day_bench(1, "default", 1000),
+            day_bench(2, "default", 1000),
+            day_bench(3, "default", 0),
+            day_bench(4, "fast", 1000),
+        ]);
+        let run = bench_run(vec![
+            day_bench(1, "default", 1500),
+            day_bench(2, "default", 1501),
+            day_bench(3, "default", 1000),
+            day_bench(4, "default", 2000),
+            day_bench(5, "default", 2000),
+        ]);
+
+        assert_eq!(regressions(&run, &baseline, 50.0), [YearDay::new(2022, 2)]);
+        assert_eq!(
+            regressions(&run, &baseline, 49.0),
+            [YearDay::new(2022, 1), YearDay::new(2022, 2)]
+        );
+    }
+}
@@ -1,17 +1,29 @@
-use aoc::util::GenericResult;
+use aoc::{inputs::DEFAULT_PROFILE, util::GenericResult};
 use ini::Ini;
 use regex::Regex;
+use std::collections::BTreeMap;
 
 pub const DEFAULT_CONFIG_PATH: &'static str = "aoc_config.ini";
 pub const KEY_SESSION_KEY: &'static str = "session_key";
 pub const KEY_EDITOR_AFTER_SCAFFOLD: &'static str = "editor_after_scaffold";
 pub const KEY_COPY_RESULT_TO_CLIPBOARD: &'static str = "copy_result_to_clipboard";
+pub const KEY_PRE_SCAFFOLD_HOOK: &str = "pre_scaffold_hook";
+pub const KEY_POST_SCAFFOLD_HOOK: &str = "post_scaffold_hook";
+/// Section of the session keys of the profiles other than the default one, as `<profile> = <session key>`.
+pub const SECTION_PROFILES: &str = "profiles";
 
 #[derive(Default, Clone)]
 pub struct Config {
-    pub session_key: Option<String>,
+    /// Session keys by profile. The session key of the default profile is stored as `session_key`.
+    pub session_keys: BTreeMap<String, String>,
+    /// Command opening the scaffolded files, e.g. `code {files}` or `$EDITOR +{line} {file}`.
+    /// Defaults to the editor in the VISUAL or EDITOR environment variable, see [`crate::hooks::editor_command`].
     pub editor_after_scaffold: Option<String>,
     pub copy_result_to_clipboard: bool,
+    /// Command run before scaffolding each day, with the placeholders of [`crate::hooks::ScaffoldValues`].
+    pub pre_scaffold_hook: Option<String>,
+    /// Command run after scaffolding and building each day, with the placeholders of [`crate::hooks::ScaffoldValues`].
+    pub post_scaffold_hook: Option<String>,
 }
 impl Config {
     pub fn load_from_file(config_file_path: &str) -> GenericResult<Config> {
@@ -20,24 +32,37 @@ impl Config {
         let section = conf
             .section(None::<String>)
             .ok_or("config file should contain correct section")?;
-        let session_key = section.get(KEY_SESSION_KEY).and_then(|x| match x {
+        let mut session_keys = BTreeMap::new();
+        if let Some(session_key) = section.get(KEY_SESSION_KEY).and_then(|x| match x {
             x if whitespace.is_match(x) => None,
             _ => Some(x.to_owned()),
-        });
-        let editor_after_scaffold = section
-            .get(KEY_EDITOR_AFTER_SCAFFOLD)
-            .and_then(|x| match x {
+        }) {
+            session_keys.insert(DEFAULT_PROFILE.to_owned(), session_key);
+        }
+        if let Some(profiles) = conf.section(Some(SECTION_PROFILES)) {
+            for (profile, session_key) in profiles.iter() {
+                if !whitespace.is_match(session_key) {
+                    session_keys.insert(profile.to_owned(), session_key.to_owned());
+                }
+            }
+        }
+        let optional = |key: &str| {
+            section.get(key).and_then(|x| match x {
                 x if whitespace.is_match(x) => None,
                 _ => Some(x.to_owned()),
-            });
+            })
+        };
+        let editor_after_scaffold = optional(KEY_EDITOR_AFTER_SCAFFOLD);
         let copy_result_to_clipboard = section
             .get(KEY_COPY_RESULT_TO_CLIPBOARD)
             .map(|x| x.to_lowercase() == "true")
             .ok_or("config does should contain copy_result_to_clipboard")?;
         Ok(Config {
-            session_key: session_key.to_owned(),
+            session_keys,
             editor_after_scaffold,
             copy_result_to_clipboard,
+            pre_scaffold_hook: optional(KEY_PRE_SCAFFOLD_HOOK),
+            post_scaffold_hook: optional(KEY_POST_SCAFFOLD_HOOK),
         })
     }
 
@@ -47,8 +72,8 @@ impl Config {
             .set(
                 KEY_SESSION_KEY,
                 &self
-                    .session_key
-                    .as_ref()
+                    .session_keys
+                    .get(DEFAULT_PROFILE)
                     .unwrap_or(&"".to_owned())
                     .to_string(),
             )
@@ -67,7 +92,26 @@ impl Config {
                 } else {
                     "false"
                 },
+            )
+            .set(
+                KEY_PRE_SCAFFOLD_HOOK,
+                self.pre_scaffold_hook.as_deref().unwrap_or_default(),
+            )
+            .set(
+                KEY_POST_SCAFFOLD_HOOK,
+                self.post_scaffold_hook.as_deref().unwrap_or_default(),
+            );
+        for (profile, session_key) in self
+            .session_keys
+            .iter()
+            .filter(|(profile, _)| *profile != DEFAULT_PROFILE)
+        {
+            conf.set_to(
+                Some(SECTION_PROFILES),
+                profile.to_owned(),
+                session_key.to_owned(),
             );
+        }
         conf.write_to_file(config_file_path)?;
 
         Ok(())
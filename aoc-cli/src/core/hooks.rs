@@ -0,0 +1,185 @@
+use crate::config::Config;
+use aoc::util::{day_str, GenericResult};
+use itertools::Itertools;
+use regex::{Captures, Regex};
+use std::env;
+use std::process::Command;
+
+/// Environment variables of the preferred editor in order, used if no editor is configured.
+const EDITOR_VARIABLES: [&str; 2] = ["VISUAL", "EDITOR"];
+
+const FILE_PLACEHOLDER: &str = "{file}";
+const FILES_PLACEHOLDER: &str = "{files}";
+
+/// Values of the placeholders in the commands run around scaffolding a day:
+/// `{year}`, `{day}`, `{day_str}`, `{files}`, `{file}` and `{line}`.
+pub struct ScaffoldValues {
+    pub year: i32,
+    pub day: u32,
+    /// Paths of the scaffolded files, ending with the solution if it is scaffolded.
+    pub files: Vec<String>,
+    /// Line of `{file}` to open the editor at, starting from 1.
+    pub line: usize,
+}
+impl ScaffoldValues {
+    /// Replaces the placeholders of the command template, quoting the paths for the shell.
+    /// Unknown placeholders are kept, so that e.g. `${EDITOR}` still reaches the shell.
+    pub fn expand(&self, template: &str) -> String {
+        let placeholder_re = Regex::new(r"\{(\w+)\}").unwrap();
+        let replaced = placeholder_re.replace_all(template, |c: &Captures| match &c[1] {
+            "year" => self.year.to_string(),
+            "day" => self.day.to_string(),
+            "day_str" => day_str(self.day),
+            "files" => self.files.iter().map(|f| quote(f)).join(" "),
+            "file" => quote(self.files.last().map_or("", |f| f.as_str())),
+            "line" => self.line.to_string(),
+            _ => c[0].to_owned(),
+        });
+
+        replaced.into_owned()
+    }
+}
+
+/// Returns the command template opening the scaffolded files: the configured one,
+/// or the editor in the VISUAL or EDITOR environment variable.
+/// The files are appended to templates without a file placeholder, e.g. `code` opens all of them.
+pub fn editor_command(config: &Config) -> Option<String> {
+    let editor = config.editor_after_scaffold.clone().or_else(|| {
+        EDITOR_VARIABLES
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|editor| !editor.trim().is_empty())
+    })?;
+    match editor.contains(FILE_PLACEHOLDER) || editor.contains(FILES_PLACEHOLDER) {
+        true => Some(editor),
+        false => Some(format!("{} {}", editor, FILES_PLACEHOLDER)),
+    }
+}
+
+/// Runs the command line with the shell of the platform, attached to the current terminal.
+pub fn run_shell(command: &str) -> GenericResult {
+    let status = shell_command(command).status()?;
+    match status.success() {
+        true => Ok(()),
+        false => Err(format!("'{}' exited with {}", command, status))?,
+    }
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+    let mut shell = Command::new("cmd");
+    // Passed as is, as cmd does not follow the usual quoting rules
+    shell.arg("/C").raw_arg(command);
+    shell
+}
+
+#[cfg(not(windows))]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn quote(arg: &str) -> String {
+    format!("\"{}\"", arg)
+}
+
+#[cfg(not(windows))]
+fn quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> ScaffoldValues {
+        ScaffoldValues {
+            year: 2022,
+            day: 5,
+            files: vec![
+                "my inputs/day05.txt".to_owned(),
+                "it's here/day05.rs".to_owned(),
+            ],
+            line: 12,
+        }
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn quote_escapes_single_quotes() {
+        assert_eq!(quote("a b"), "'a b'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(quote(""), "''");
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn quote_wraps_in_double_quotes() {
+        assert_eq!(quote("a b"), "\"a b\"");
+        assert_eq!(quote("it's"), "\"it's\"");
+    }
+
+    #[test]
+    fn expand_replaces_the_placeholders() {
+        let command = values().expand("{year}/{day}/{day_str} +{line} {file} -- {files}");
+        assert_eq!(
+            command,
+            format!(
+                "2022/5/{} +12 {} -- {} {}",
+                day_str(5),
+                quote("it's here/day05.rs"),
+                quote("my inputs/day05.txt"),
+                quote("it's here/day05.rs")
+            )
+        );
+    }
+
+    #[test]
+    fn expand_keeps_unknown_placeholders() {
+        let command = values().expand("${EDITOR} {unknown} {day} {} {{day}}");
+        assert_eq!(command, "${EDITOR} {unknown} 5 {} {5}");
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn expanded_paths_reach_the_command_unchanged() {
+        let values = values();
+        let command = values.expand("printf '%s\\n' {files}");
+        let output = shell_command(&command).output().unwrap();
+        let lines = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(lines.lines().collect_vec(), values.files);
+    }
+
+    #[test]
+    fn editor_command_appends_the_files_without_a_file_placeholder() {
+        let editor = |command: &str| {
+            editor_command(&Config {
+                editor_after_scaffold: Some(command.to_owned()),
+                ..Default::default()
+            })
+        };
+        assert_eq!(editor("code").as_deref(), Some("code {files}"));
+        assert_eq!(
+            editor("vim +{line} {file}").as_deref(),
+            Some("vim +{line} {file}")
+        );
+        assert_eq!(editor("subl {files}").as_deref(), Some("subl {files}"));
+    }
+
+    #[test]
+    fn editor_command_falls_back_to_the_environment() {
+        // The only test reading these variables
+        env::set_var("VISUAL", " ");
+        env::set_var("EDITOR", "nano");
+        assert_eq!(
+            editor_command(&Config::default()).as_deref(),
+            Some("nano {files}")
+        );
+        env::remove_var("EDITOR");
+        assert_eq!(editor_command(&Config::default()), None);
+        env::remove_var("VISUAL");
+    }
+}
@@ -0,0 +1,618 @@
+use crate::isolate::ProcessLimits;
+use crate::serve::{self, ServeRequest, ServeResponse};
+use aoc::{core::solution_runner::*, solution::CancellationToken, util::GenericResult};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, TcpListener},
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Directory of the web UI built by `npm run build`.
+pub const DEFAULT_WEB_DIR: &str = "dist";
+
+/// Largest request body accepted, larger ones are answered with 413.
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+/// Time given to clients to start streaming the events of a run, after which it is cancelled and removed.
+const SUBSCRIBE_TIMEOUT: Duration = Duration::from_secs(60);
+/// How often runs exceeding the [`SUBSCRIBE_TIMEOUT`] are looked for.
+const REAP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Started run waiting for its events to be streamed.
+struct Run {
+    cancellation_token: CancellationToken,
+    started: Instant,
+    /// Taken by the first client streaming the events.
+    rx: Option<ProgressReceiver>,
+}
+
+/// State shared by the connections.
+struct Server {
+    /// Local port of the server, the only origin allowed to start and cancel runs.
+    port: u16,
+    options: SolveOptions,
+    isolation: Option<ProcessLimits>,
+    web_dir: PathBuf,
+    runs: Mutex<HashMap<u64, Run>>,
+    next_id: Mutex<u64>,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    /// Origin of the page sending the request, set by browsers.
+    origin: Option<String>,
+    /// Length of the body announced by the client, the body is only read up to [`MAX_BODY_SIZE`].
+    content_length: usize,
+    body: String,
+}
+
+#[derive(Serialize)]
+struct RunStarted {
+    id: u64,
+}
+
+/// Serves the REST API and the web UI on the local machine, handling each connection on its own thread:
+/// - `GET /api/solutions` lists the implemented solutions.
+/// - `POST /api/runs` starts a `StartDay` or `StartDays` request and returns the id of the run.
+/// - `GET /api/runs/<id>/events` streams the [`SolveProgress`] events of the run as Server-Sent Events.
+/// - `POST /api/runs/<id>/cancel` cancels the run.
+///
+/// Runs whose events are not streamed within [`SUBSCRIBE_TIMEOUT`] are cancelled and removed.
+/// POST requests sent by pages of other origins are rejected.
+pub fn serve_http(
+    port: u16,
+    web_dir: PathBuf,
+    options: SolveOptions,
+    isolation: Option<ProcessLimits>,
+) -> GenericResult {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    println!("Serving on http://{}", listener.local_addr()?);
+    if !web_dir.join("index.html").is_file() {
+        println!(
+            "Warning: the web UI is not found in '{}', build it with 'npm run build'.",
+            web_dir.display()
+        );
+    }
+
+    let server = Arc::new(Server {
+        port: listener.local_addr()?.port(),
+        options,
+        isolation,
+        web_dir,
+        runs: Mutex::new(HashMap::new()),
+        next_id: Mutex::new(1),
+    });
+    let reaper = Arc::clone(&server);
+    thread::spawn(move || loop {
+        thread::sleep(REAP_INTERVAL);
+        remove_expired(&reaper, Instant::now());
+    });
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let server = Arc::clone(&server);
+        thread::spawn(move || {
+            if let Err(err) = handle_connection(&server, stream) {
+                eprintln!("Error: {}", err);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+fn handle_connection<S: Read + Write>(server: &Arc<Server>, mut stream: S) -> GenericResult {
+    let request = match read_request(&mut stream)? {
+        Some(request) => request,
+        None => return Ok(()),
+    };
+    if request.content_length > MAX_BODY_SIZE {
+        return write_error(
+            &mut stream,
+            "413 Payload Too Large",
+            "request body too large",
+        );
+    }
+    // Keep other web pages open in the browser from starting runs with simple requests, which are not preflighted
+    if request.method == "POST" && !is_own_origin(server, request.origin.as_deref()) {
+        return write_error(&mut stream, "403 Forbidden", "origin not allowed");
+    }
+    let segments = request
+        .path
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .trim_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["api", "solutions"]) => {
+            write_json(&mut stream, "200 OK", &serve::list_solutions())
+        }
+        ("POST", ["api", "runs"]) => start_run(server, &mut stream, &request.body),
+        ("GET", ["api", "runs", id, "events"]) => stream_events(server, &mut stream, id),
+        ("POST", ["api", "runs", id, "cancel"]) => {
+            let runs = server.runs.lock().unwrap();
+            match id.parse().ok().and_then(|id: u64| runs.get(&id)) {
+                Some(run) => {
+                    run.cancellation_token.cancel();
+                    write_response(&mut stream, "204 No Content", &[], b"")
+                }
+                None => write_error(&mut stream, "404 Not Found", "run not found"),
+            }
+        }
+        ("GET", ["api", ..]) | ("POST", _) => {
+            write_error(&mut stream, "404 Not Found", "not found")
+        }
+        ("GET", _) => serve_file(server, &mut stream, &segments),
+        _ => write_error(&mut stream, "405 Method Not Allowed", "method not allowed"),
+    }
+}
+
+/// Returns whether the request comes from a page served by this server, or not from a browser at all.
+fn is_own_origin(server: &Server, origin: Option<&str>) -> bool {
+    match origin {
+        Some(origin) => ["localhost", "127.0.0.1"]
+            .iter()
+            .any(|host| origin == format!("http://{}:{}", host, server.port)),
+        None => true,
+    }
+}
+
+/// Reads the request line, the headers and the body of a request. Returns None if the connection was closed.
+fn read_request(stream: &mut impl Read) -> GenericResult<Option<Request>> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method.to_owned(), path.to_owned()),
+        _ => Err("invalid request line")?,
+    };
+
+    let mut origin = None;
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse()?;
+            } else if name.eq_ignore_ascii_case("origin") {
+                origin = Some(value.trim().to_owned());
+            }
+        }
+    }
+    let mut body = Vec::new();
+    if content_length <= MAX_BODY_SIZE {
+        body.resize(content_length, 0);
+        reader.read_exact(&mut body)?;
+    }
+
+    Ok(Some(Request {
+        method,
+        path,
+        origin,
+        content_length,
+        body: String::from_utf8(body)?,
+    }))
+}
+
+fn start_run(server: &Arc<Server>, stream: &mut impl Write, body: &str) -> GenericResult {
+    let request = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(err) => {
+            return write_error(
+                stream,
+                "400 Bad Request",
+                &format!("invalid request: {}", err),
+            )
+        }
+    };
+    let (cancellation_token, rx) = match request {
+        ServeRequest::StartDay(year_day, input) => {
+            serve::start_run(&server.options, &server.isolation, |solver| {
+                solver.run(year_day, input)
+            })
+        }
+        ServeRequest::StartDays(days) => {
+            serve::start_run(&server.options, &server.isolation, |solver| {
+                solver.run_many(days)
+            })
+        }
+        _ => return write_error(stream, "400 Bad Request", "only runs can be started"),
+    };
+
+    let id = {
+        let mut next_id = server.next_id.lock().unwrap();
+        *next_id += 1;
+        *next_id - 1
+    };
+    server.runs.lock().unwrap().insert(
+        id,
+        Run {
+            cancellation_token,
+            started: Instant::now(),
+            rx: Some(rx),
+        },
+    );
+    write_json(stream, "201 Created", &RunStarted { id })
+}
+
+/// Cancels and removes the runs whose events are still not streamed after the [`SUBSCRIBE_TIMEOUT`],
+/// dropping the events sent so far.
+fn remove_expired(server: &Server, now: Instant) {
+    server.runs.lock().unwrap().retain(|_, run| {
+        let expired = run.rx.is_some() && now.duration_since(run.started) >= SUBSCRIBE_TIMEOUT;
+        if expired {
+            run.cancellation_token.cancel();
+        }
+        !expired
+    });
+}
+
+/// Streams the events of the run until it finishes, followed by an `end` event.
+/// The run is cancelled if the client disconnects before that.
+fn stream_events(server: &Server, stream: &mut impl Write, id: &str) -> GenericResult {
+    let id = id.parse::<u64>().ok();
+    let rx = id.and_then(|id| server.runs.lock().unwrap().get_mut(&id)?.rx.take());
+    let (id, mut rx) = match (id, rx) {
+        (Some(id), Some(rx)) => (id, rx),
+        _ => return write_error(stream, "404 Not Found", "run not found or already streamed"),
+    };
+
+    let result = send_events(stream, &mut rx);
+    if let Some(run) = server.runs.lock().unwrap().remove(&id) {
+        run.cancellation_token.cancel();
+    }
+    Ok(result?)
+}
+
+fn send_events(stream: &mut impl Write, rx: &mut ProgressReceiver) -> io::Result<()> {
+    let headers = [
+        ("Content-Type", "text/event-stream"),
+        ("Cache-Control", "no-cache"),
+    ];
+    write_head(stream, "200 OK", &headers)?;
+    while let Some(progress) = rx.recv() {
+        let json = serde_json::to_string(&progress).expect("progress should be serializable");
+        write!(stream, "data: {}\n\n", json)?;
+        stream.flush()?;
+    }
+    write!(stream, "event: end\ndata:\n\n")?;
+    stream.flush()
+}
+
+/// Serves a file of the web UI, or its index for the root path.
+fn serve_file(server: &Server, stream: &mut impl Write, segments: &[&str]) -> GenericResult {
+    let relative = match segments {
+        [""] => PathBuf::from("index.html"),
+        segments => PathBuf::from_iter(segments),
+    };
+    // Only serve files inside the web directory
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return write_error(stream, "404 Not Found", "not found");
+    }
+
+    let path = server.web_dir.join(relative);
+    match fs::read(&path) {
+        Ok(contents) => write_response(
+            stream,
+            "200 OK",
+            &[("Content-Type", content_type(&path))],
+            &contents,
+        ),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            write_error(stream, "404 Not Found", "not found")
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn content_type(path: &Path) -> &str {
+    match path.extension().and_then(|x| x.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "text/javascript",
+        Some("css") => "text/css",
+        Some("wasm") => "application/wasm",
+        Some("json") => "application/json",
+        Some("ico") => "image/x-icon",
+        Some("png") => "image/png",
+        Some("txt") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+fn write_head(stream: &mut impl Write, status: &str, headers: &[(&str, &str)]) -> io::Result<()> {
+    let mut head = format!("HTTP/1.1 {}\r\nConnection: close\r\n", status);
+    for (name, value) in headers {
+        head += &format!("{}: {}\r\n", name, value);
+    }
+    head += "\r\n";
+    stream.write_all(head.as_bytes())
+}
+
+fn write_response(
+    stream: &mut impl Write,
+    status: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> GenericResult {
+    let content_length = body.len().to_string();
+    let headers = headers
+        .iter()
+        .copied()
+        .chain([("Content-Length", content_length.as_str())])
+        .collect::<Vec<_>>();
+    write_head(stream, status, &headers)?;
+    stream.write_all(body)?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn write_json<T: Serialize>(stream: &mut impl Write, status: &str, value: &T) -> GenericResult {
+    let body = serde_json::to_string(value)?;
+    write_response(
+        stream,
+        status,
+        &[("Content-Type", "application/json")],
+        body.as_bytes(),
+    )
+}
+
+fn write_error(stream: &mut impl Write, status: &str, message: &str) -> GenericResult {
+    write_json(
+        stream,
+        status,
+        &ServeResponse::RequestError(message.to_owned()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::util::YearDay;
+    use std::io::Cursor;
+
+    const PORT: u16 = 8022;
+    const DAY1_INPUT: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
+    /// Connection reading the request from memory and collecting the response.
+    struct Connection {
+        request: Cursor<Vec<u8>>,
+        response: Vec<u8>,
+    }
+    impl Read for Connection {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.request.read(buf)
+        }
+    }
+    impl Write for Connection {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.response.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn server() -> Arc<Server> {
+        Arc::new(Server {
+            port: PORT,
+            options: Default::default(),
+            isolation: None,
+            web_dir: PathBuf::from("missing-web-dir"),
+            runs: Mutex::new(HashMap::new()),
+            next_id: Mutex::new(1),
+        })
+    }
+
+    fn raw_request(method: &str, path: &str, headers: &[&str], body: &str) -> String {
+        let mut request = format!("{} {} HTTP/1.1\r\nHost: localhost\r\n", method, path);
+        for header in headers {
+            request += &format!("{}\r\n", header);
+        }
+        format!("{}Content-Length: {}\r\n\r\n{}", request, body.len(), body)
+    }
+
+    /// Handles the raw request, returning the status line and the body of the response.
+    fn send(server: &Arc<Server>, raw_request: &str) -> (String, String) {
+        let mut connection = Connection {
+            request: Cursor::new(raw_request.as_bytes().to_vec()),
+            response: Vec::new(),
+        };
+        handle_connection(server, &mut connection).unwrap();
+        let response = String::from_utf8(connection.response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.lines().next().unwrap().to_owned();
+        (status, body.to_owned())
+    }
+
+    fn start_day_json() -> String {
+        let request =
+            ServeRequest::StartDay(YearDay::new(2022, 1), Input::Custom(DAY1_INPUT.to_owned()));
+        serde_json::to_string(&request).unwrap()
+    }
+
+    #[test]
+    fn request_is_parsed_with_case_insensitive_headers() {
+        let raw = "POST /api/runs?x=1 HTTP/1.1\r\norigin: http://localhost:8022\r\nCONTENT-LENGTH: 4\r\n\r\nbody";
+        let request = read_request(&mut Cursor::new(raw)).unwrap().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/api/runs?x=1");
+        assert_eq!(request.origin.as_deref(), Some("http://localhost:8022"));
+        assert_eq!(request.content_length, 4);
+        assert_eq!(request.body, "body");
+
+        assert!(read_request(&mut Cursor::new("")).unwrap().is_none());
+        assert!(read_request(&mut Cursor::new("GARBAGE\r\n\r\n")).is_err());
+    }
+
+    #[test]
+    fn large_body_is_rejected_without_reading_it() {
+        let raw = format!(
+            "POST /api/runs HTTP/1.1\r\nContent-Length: {}\r\n\r\n{{",
+            MAX_BODY_SIZE + 1
+        );
+        let (status, body) = send(&server(), &raw);
+        assert_eq!(status, "HTTP/1.1 413 Payload Too Large");
+        assert_eq!(body, r#"{"RequestError":"request body too large"}"#);
+    }
+
+    #[test]
+    fn post_is_only_accepted_from_own_origin() {
+        let server = server();
+        for origin in [
+            "Origin: http://evil.example",
+            "Origin: http://localhost:8023",
+            "Origin: https://localhost:8022",
+        ] {
+            let (status, _) = send(
+                &server,
+                &raw_request("POST", "/api/runs/1/cancel", &[origin], ""),
+            );
+            assert_eq!(status, "HTTP/1.1 403 Forbidden", "{}", origin);
+        }
+        for headers in [
+            &["Origin: http://localhost:8022"][..],
+            &["Origin: http://127.0.0.1:8022"],
+            &[],
+        ] {
+            let (status, _) = send(
+                &server,
+                &raw_request("POST", "/api/runs/1/cancel", headers, ""),
+            );
+            assert_eq!(status, "HTTP/1.1 404 Not Found", "{:?}", headers);
+        }
+
+        let raw = raw_request(
+            "GET",
+            "/api/solutions",
+            &["Origin: http://evil.example"],
+            "",
+        );
+        assert_eq!(send(&server, &raw).0, "HTTP/1.1 200 OK");
+    }
+
+    #[test]
+    fn invalid_requests_are_answered_with_errors() {
+        let server = server();
+        let cases = [
+            (
+                raw_request("POST", "/api/runs", &[], "{"),
+                "400 Bad Request",
+            ),
+            (
+                raw_request("POST", "/api/runs", &[], r#""Cancel""#),
+                "400 Bad Request",
+            ),
+            (
+                raw_request("GET", "/api/runs/x/events", &[], ""),
+                "404 Not Found",
+            ),
+            (raw_request("GET", "/api/unknown", &[], ""), "404 Not Found"),
+            (
+                raw_request("GET", "/../Cargo.toml", &[], ""),
+                "404 Not Found",
+            ),
+            (
+                raw_request("DELETE", "/api/runs/1", &[], ""),
+                "405 Method Not Allowed",
+            ),
+        ];
+        for (raw, expected) in cases {
+            let (status, _) = send(&server, &raw);
+            assert_eq!(status, format!("HTTP/1.1 {}", expected), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn events_of_a_run_are_streamed_once() {
+        let server = server();
+        let (status, body) = send(
+            &server,
+            &raw_request("POST", "/api/runs", &[], &start_day_json()),
+        );
+        assert_eq!(status, "HTTP/1.1 201 Created");
+        assert_eq!(body, r#"{"id":1}"#);
+
+        let (status, body) = send(&server, &raw_request("GET", "/api/runs/1/events", &[], ""));
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        let messages = body.split_terminator("\n\n").collect::<Vec<_>>();
+        assert_eq!(messages.last(), Some(&"event: end\ndata:"));
+        let events = messages[..messages.len() - 1]
+            .iter()
+            .map(|message| {
+                let json = message
+                    .strip_prefix("data: ")
+                    .expect("message should be data");
+                serde_json::from_str::<SolveProgress>(json).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let answers = events
+            .iter()
+            .filter_map(|e| match e {
+                SolveProgress::SuccessResult(p) => Some(p.value.to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(answers, ["24000", "45000"]);
+        assert!(matches!(events.last(), Some(SolveProgress::Done(_))));
+
+        // The finished run is removed
+        assert!(server.runs.lock().unwrap().is_empty());
+        let (status, _) = send(&server, &raw_request("GET", "/api/runs/1/events", &[], ""));
+        assert_eq!(status, "HTTP/1.1 404 Not Found");
+    }
+
+    #[test]
+    fn runs_not_streamed_in_time_are_cancelled_and_removed() {
+        let server = server();
+        let start = Instant::now();
+        let mut tokens = Vec::new();
+        for (id, started, subscribed) in [
+            (1, start, false),
+            (2, start, true),
+            (3, start + SUBSCRIBE_TIMEOUT / 2, false),
+        ] {
+            let cancellation_token = CancellationToken::new();
+            tokens.push(cancellation_token.clone());
+            let rx = ChannelSyncStream::new().take_receiver();
+            let run = Run {
+                cancellation_token,
+                started,
+                rx: rx.filter(|_| !subscribed),
+            };
+            server.runs.lock().unwrap().insert(id, run);
+        }
+
+        remove_expired(&server, start + SUBSCRIBE_TIMEOUT);
+
+        let mut ids = server
+            .runs
+            .lock()
+            .unwrap()
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, [2, 3]);
+        let cancelled = tokens.iter().map(|t| t.is_cancelled()).collect::<Vec<_>>();
+        assert_eq!(cancelled, [true, false, false]);
+    }
+}
@@ -0,0 +1,588 @@
+use aoc::{
+    answers,
+    core::solution_runner::*,
+    inputs,
+    solution::LogLevel,
+    util::{GenericResult, YearDay},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    env,
+    io::{self, BufRead, BufReader, Read, Write},
+    process::{Child, Command, Stdio},
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// Hidden command of aoc-cli running a single day for a [`ProcessSolutionRunner`].
+pub const WORKER_COMMAND: &str = "worker";
+
+/// How often the cancellation and the wall-clock limit are checked while waiting for a worker.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Limits of the worker processes solving the days.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ProcessLimits {
+    /// Maximum address space of the worker in bytes. Only supported on Unix.
+    pub memory_limit: Option<u64>,
+    /// Stack size of the thread running the solution in bytes.
+    pub stack_size: Option<usize>,
+    /// Time after which the worker is killed, covering the initialization and both parts.
+    pub wall_time_limit: Option<Duration>,
+}
+
+/// Day sent to a worker process on its standard input.
+#[derive(Serialize, Deserialize)]
+struct WorkerRequest {
+    year_day: YearDay,
+    input: String,
+    variant: Option<String>,
+    fps: f32,
+    log_level: LogLevel,
+    emit_frames: bool,
+    part_time_limit: Option<Duration>,
+    memory_limit: Option<u64>,
+    stack_size: Option<usize>,
+}
+
+/// Runs each day in a child aoc-cli process, so that a stack overflow, an exhausted memory
+/// or a runaway solution only fails the given day. Only usable from the aoc-cli binary.
+/// Cancelled and timed out days are stopped by killing their process.
+#[derive(Default)]
+pub struct ProcessSolutionRunner {
+    pub options: SolveOptions,
+    pub limits: ProcessLimits,
+}
+impl<T: SyncStream + Default + 'static> SolutionRunner<T> for ProcessSolutionRunner {
+    fn run(&self, year_day: YearDay, input: Input) -> Arc<Mutex<T>> {
+        let stream = Arc::new(Mutex::new(T::default()));
+        let stream_copy = Arc::clone(&stream);
+        let options = self.options.clone();
+        let limits = self.limits.clone();
+        thread::spawn(move || run_isolated(year_day, input, stream_copy, options, &limits));
+
+        stream
+    }
+
+    fn run_many(&self, days: Vec<YearDay>) -> Arc<Mutex<T>> {
+        let stream = Arc::new(Mutex::new(T::default()));
+        let stream_copy = Arc::clone(&stream);
+        let options = self.options.clone();
+        let limits = self.limits.clone();
+        thread::spawn(move || {
+            run_many_with(&days, stream_copy, options, |year_day, tx, options| {
+                run_isolated(year_day, Input::Default, tx, options, &limits)
+            })
+        });
+
+        stream
+    }
+
+    fn cancel(&self) {
+        self.options.cancellation_token.cancel();
+    }
+}
+
+/// Returns a runner solving the days in worker processes with the given limits,
+/// or on threads of the current process without them.
+pub fn solution_runner(
+    options: SolveOptions,
+    isolation: Option<ProcessLimits>,
+) -> Box<dyn SolutionRunner<ChannelSyncStream>> {
+    match isolation {
+        Some(limits) => Box::new(ProcessSolutionRunner { options, limits }),
+        None => Box::new(ThreadSolutionRunner { options }),
+    }
+}
+
+/// Solves a day in a worker process, forwarding its events to the stream.
+/// The input is resolved and the answers are checked by the current process.
+pub fn run_isolated<T: SyncStream + ?Sized>(
+    year_day: YearDay,
+    input: Input,
+    tx: Arc<Mutex<T>>,
+    options: SolveOptions,
+    limits: &ProcessLimits,
+) {
+    let start = Instant::now();
+    // The confirmed answers belong to the default inputs
+    let expected = match input {
+        Input::Default => options.answers.parts(&year_day, &options.profile),
+        Input::Custom(_) => Default::default(),
+    };
+    let input = match input {
+        Input::Default => {
+            match inputs::resolve(&options.input_providers, &year_day, &options.profile) {
+                Ok(input) => input,
+                Err(err) => {
+                    return finish(&tx, year_day, start, SolveProgress::Error(err.to_string()))
+                }
+            }
+        }
+        Input::Custom(input) => input,
+    };
+    let request = WorkerRequest {
+        year_day,
+        input,
+        variant: options.variant.clone(),
+        fps: options.fps,
+        log_level: options.log_level,
+        emit_frames: options.emit_frames,
+        part_time_limit: options.part_time_limit,
+        memory_limit: limits.memory_limit,
+        stack_size: limits.stack_size,
+    };
+    let worker = match worker_command().and_then(|command| Worker::spawn(command, &request)) {
+        Ok(worker) => worker,
+        Err(err) => {
+            let message = format!("Unable to start worker process: {}", err);
+            return finish(&tx, year_day, start, SolveProgress::Error(message));
+        }
+    };
+
+    forward_events(worker, year_day, tx, &options, limits, &expected, start);
+}
+
+/// Forwards the events of the worker solving the given day to the stream until the day is finished,
+/// killing the worker if the day is cancelled or exceeds the wall-clock limit.
+fn forward_events<T: SyncStream + ?Sized>(
+    worker: Worker,
+    year_day: YearDay,
+    tx: Arc<Mutex<T>>,
+    options: &SolveOptions,
+    limits: &ProcessLimits,
+    expected: &BTreeMap<u8, String>,
+    start: Instant,
+) {
+    let deadline = limits.wall_time_limit.map(|limit| start + limit);
+    // The part being solved by the worker, or None during initialization
+    let mut part = None;
+    let mut part_start = start;
+    loop {
+        // Checked on every event as well, since a solution reporting progress keeps the channel busy
+        if options.cancellation_token.is_cancelled() {
+            worker.kill();
+            let mut tx = tx.lock().unwrap();
+            tx.send(SolveProgress::Cancelled(ResultPack {
+                year_day,
+                part,
+                value: (),
+                duration: start.elapsed(),
+                verdict: None,
+            }));
+            return tx.close();
+        }
+        let now = Instant::now();
+        if let (Some(deadline), Some(limit)) = (deadline, limits.wall_time_limit) {
+            if now >= deadline {
+                worker.kill();
+                let timeout = SolveProgress::Timeout(ResultPack {
+                    year_day,
+                    part,
+                    value: limit,
+                    duration: part_start.elapsed(),
+                    verdict: None,
+                });
+                return finish(&tx, year_day, start, timeout);
+            }
+        }
+
+        let wait = deadline.map_or(POLL_INTERVAL, |d| POLL_INTERVAL.min(d - now));
+        match worker.events.recv_timeout(wait) {
+            Ok(mut event) => {
+                match &mut event {
+                    SolveProgress::Initialized(_) => {
+                        part = Some(1);
+                        part_start = Instant::now();
+                    }
+                    SolveProgress::SuccessResult(p) => {
+                        let known = p.part.and_then(|part| expected.get(&part));
+                        p.verdict = Some(answers::check(known.map(|a| a.as_str()), &p.value));
+                        part = p.part.map(|part| part + 1);
+                        part_start = Instant::now();
+                    }
+                    _ => (),
+                }
+                let finished =
+                    matches!(event, SolveProgress::Done(_) | SolveProgress::Cancelled(_));
+                let mut tx = tx.lock().unwrap();
+                tx.send(event);
+                if finished {
+                    tx.close();
+                    return worker.wait();
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+            // Ctrl-C may also have stopped the worker, which is reported as a cancellation above
+            Err(RecvTimeoutError::Disconnected) if options.cancellation_token.is_cancelled() => (),
+            // The worker exited without finishing the day
+            Err(RecvTimeoutError::Disconnected) => {
+                let reason = worker.failure();
+                let error = match part {
+                    Some(part) => SolveProgress::ErrorResult(ResultPack {
+                        year_day,
+                        part: Some(part),
+                        value: reason,
+                        duration: part_start.elapsed(),
+                        verdict: None,
+                    }),
+                    None => {
+                        SolveProgress::Error(format!("Unable to initialize solution: {}", reason))
+                    }
+                };
+                return finish(&tx, year_day, start, error);
+            }
+        }
+    }
+}
+
+/// Sends the given event followed by [`SolveProgress::Done`], then closes the stream.
+fn finish<T: SyncStream + ?Sized>(
+    tx: &Arc<Mutex<T>>,
+    year_day: YearDay,
+    start: Instant,
+    event: SolveProgress,
+) {
+    let mut tx = tx.lock().unwrap();
+    tx.send(event);
+    tx.send(SolveProgress::Done(ResultPack {
+        year_day,
+        part: None,
+        value: (),
+        duration: start.elapsed(),
+        verdict: None,
+    }));
+    tx.close();
+}
+
+/// Returns the command running the worker with the current executable.
+fn worker_command() -> GenericResult<Command> {
+    let mut command = Command::new(env::current_exe()?);
+    command.arg(WORKER_COMMAND);
+    Ok(command)
+}
+
+/// Child process solving a single day.
+struct Worker {
+    child: Child,
+    events: Receiver<SolveProgress>,
+    /// Collects the standard error, which describes the crash of the worker.
+    stderr: JoinHandle<String>,
+}
+impl Worker {
+    /// Starts the worker with the given command, sending the request to its standard input.
+    fn spawn(mut command: Command, request: &WorkerRequest) -> GenericResult<Worker> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        // Closing the standard input lets the worker start
+        let stdin = child.stdin.take().expect("stdin should be piped");
+        serde_json::to_writer(stdin, request)?;
+
+        let stdout = child.stdout.take().expect("stdout should be piped");
+        let (tx, events) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                // Skip anything printed directly by the solution
+                if let Ok(event) = serde_json::from_str(&line) {
+                    if tx.send(event).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        let mut stderr = child.stderr.take().expect("stderr should be piped");
+        let stderr = thread::spawn(move || {
+            let mut output = String::new();
+            _ = stderr.read_to_string(&mut output);
+            output
+        });
+
+        Ok(Worker {
+            child,
+            events,
+            stderr,
+        })
+    }
+
+    fn wait(mut self) {
+        _ = self.child.wait();
+    }
+
+    fn kill(mut self) {
+        _ = self.child.kill();
+        _ = self.child.wait();
+    }
+
+    /// Describes why the worker exited, with the first line of its standard error,
+    /// e.g. "thread '<unnamed>' has overflowed its stack" or "memory allocation of 1024 bytes failed".
+    fn failure(mut self) -> String {
+        let status = self.child.wait();
+        let stderr = self.stderr.join().unwrap_or_default();
+        let first_line = stderr.lines().map(str::trim).find(|l| !l.is_empty());
+        match (status, first_line) {
+            (Ok(status), Some(line)) => format!("worker process exited with {}: {}", status, line),
+            (Ok(status), None) => format!("worker process exited with {}", status),
+            (Err(err), _) => format!("worker process failed: {}", err),
+        }
+    }
+}
+
+/// Writes the events to the standard output, one JSON per line.
+struct StdoutSyncStream;
+impl SyncStream for StdoutSyncStream {
+    fn send(&mut self, item: SolveProgress) {
+        let json = serde_json::to_string(&item).expect("progress should be serializable");
+        let mut stdout = io::stdout().lock();
+        // The parent process may have stopped listening
+        _ = writeln!(stdout, "{}", json);
+        _ = stdout.flush();
+    }
+
+    fn close(&mut self) {}
+
+    fn next_items(&mut self) -> Option<Vec<SolveProgress>> {
+        None
+    }
+}
+
+/// Solves the day read from the standard input within the requested limits,
+/// writing the events to the standard output. Run by the [`WORKER_COMMAND`].
+pub fn run_worker() -> GenericResult {
+    let request: WorkerRequest = serde_json::from_reader(io::stdin().lock())?;
+    if let Some(limit) = request.memory_limit {
+        set_memory_limit(limit)?;
+    }
+
+    let options = SolveOptions {
+        fps: request.fps,
+        part_time_limit: request.part_time_limit,
+        variant: request.variant,
+        log_level: request.log_level,
+        emit_frames: request.emit_frames,
+        // The input is resolved and the answers are checked by the parent process
+        input_providers: Vec::new(),
+        answers: Default::default(),
+        ..Default::default()
+    };
+    let (year_day, input) = (request.year_day, Input::Custom(request.input));
+    let tx = Arc::new(Mutex::new(StdoutSyncStream));
+    let mut builder = thread::Builder::new();
+    if let Some(stack_size) = request.stack_size {
+        builder = builder.stack_size(stack_size);
+    }
+    builder
+        .spawn(move || run_solution(year_day, input, tx, options))?
+        .join()
+        .map_err(|_| "solution thread panicked")?;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_memory_limit(bytes: u64) -> GenericResult {
+    let limit = libc::rlimit {
+        rlim_cur: bytes as libc::rlim_t,
+        rlim_max: bytes as libc::rlim_t,
+    };
+    // SAFETY: setrlimit only reads the given struct
+    match unsafe { libc::setrlimit(libc::RLIMIT_AS, &limit) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error().into()),
+    }
+}
+
+#[cfg(not(unix))]
+fn set_memory_limit(_bytes: u64) -> GenericResult {
+    Err("memory limits are only supported on Unix")?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::{answers::Verdict, solution::Answer};
+    use itertools::Itertools;
+
+    fn year_day() -> YearDay {
+        YearDay::new(2022, 1)
+    }
+
+    fn request() -> WorkerRequest {
+        WorkerRequest {
+            year_day: year_day(),
+            input: "1\n2\n".to_owned(),
+            variant: Some("fast".to_owned()),
+            fps: 30.0,
+            log_level: LogLevel::Debug,
+            emit_frames: true,
+            part_time_limit: Some(Duration::from_millis(1500)),
+            memory_limit: Some(1 << 30),
+            stack_size: Some(1 << 24),
+        }
+    }
+
+    fn pack<T>(part: Option<u8>, value: T) -> ResultPack<T> {
+        ResultPack {
+            year_day: year_day(),
+            part,
+            value,
+            duration: Duration::from_millis(3),
+            verdict: None,
+        }
+    }
+
+    fn json(event: &SolveProgress) -> serde_json::Value {
+        serde_json::to_value(event).unwrap()
+    }
+
+    /// Starts a shell script as the worker, which reads the request and receives the given lines as its arguments.
+    #[cfg(unix)]
+    fn fake_worker(script: &str, lines: &[String]) -> Worker {
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(format!("cat > /dev/null; {}", script))
+            .arg("sh")
+            .args(lines);
+        Worker::spawn(command, &request()).unwrap()
+    }
+
+    #[cfg(unix)]
+    fn forward(worker: Worker, limits: ProcessLimits) -> Vec<SolveProgress> {
+        let stream = Arc::new(Mutex::new(LocalSyncStream::new()));
+        let expected = BTreeMap::from([(1, "1".to_owned())]);
+        let options = SolveOptions::default();
+        let start = Instant::now();
+        forward_events(
+            worker,
+            year_day(),
+            Arc::clone(&stream),
+            &options,
+            &limits,
+            &expected,
+            start,
+        );
+
+        let mut stream = stream.lock().unwrap();
+        stream.next_items().unwrap()
+    }
+
+    #[test]
+    fn request_survives_serialization() {
+        let json = serde_json::to_string(&request()).unwrap();
+        let request: WorkerRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&request).unwrap(), json);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn events_of_the_worker_are_forwarded_with_verdicts() {
+        let events = [
+            SolveProgress::Start(year_day(), "Calorie Counting".to_owned()),
+            SolveProgress::Initialized(pack(None, ())),
+            SolveProgress::Progress(pack(Some(1), 0.5)),
+            SolveProgress::SuccessResult(pack(Some(1), Answer::Integer(1))),
+            SolveProgress::Log(pack(
+                Some(2),
+                LogMessage {
+                    level: LogLevel::Info,
+                    message: "a \"quoted\"\nmessage".to_owned(),
+                },
+            )),
+            SolveProgress::Frame(pack(Some(2), vec!["#.".to_owned(), ".#".to_owned()])),
+            SolveProgress::SuccessResult(pack(Some(2), Answer::Text("two".to_owned()))),
+            SolveProgress::Done(pack(None, ())),
+        ];
+        let mut lines = events.iter().map(|e| json(e).to_string()).collect_vec();
+        // Printed directly by the solution
+        lines.insert(2, "not an event".to_owned());
+
+        let worker = fake_worker(r#"printf '%s\n' "$@""#, &lines);
+        let forwarded = forward(worker, Default::default());
+
+        let mut expected = events.to_vec();
+        for (index, verdict) in [(3, Verdict::Correct), (6, Verdict::Unknown)] {
+            if let SolveProgress::SuccessResult(p) = &mut expected[index] {
+                p.verdict = Some(verdict);
+            }
+        }
+        assert_eq!(
+            forwarded.iter().map(json).collect_vec(),
+            expected.iter().map(json).collect_vec()
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn crashed_worker_fails_its_part() {
+        let lines = [
+            SolveProgress::Start(year_day(), String::new()),
+            SolveProgress::Initialized(pack(None, ())),
+        ]
+        .iter()
+        .map(|e| json(e).to_string())
+        .collect_vec();
+        let script =
+            r#"printf '%s\n' "$@"; echo "thread 'main' has overflowed its stack" >&2; exit 3"#;
+        let events = forward(fake_worker(script, &lines), Default::default());
+
+        assert!(
+            matches!(&events[..], [
+                SolveProgress::Start(..),
+                SolveProgress::Initialized(_),
+                SolveProgress::ErrorResult(error),
+                SolveProgress::Done(_)
+            ] if error.part == Some(1)
+                && error.value == "worker process exited with exit status: 3: thread 'main' has overflowed its stack"),
+            "got {:?}",
+            events
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn garbled_worker_fails_the_initialization() {
+        let events = forward(
+            fake_worker(r#"echo '{"Start": garbled'"#, &[]),
+            Default::default(),
+        );
+
+        assert!(
+            matches!(&events[..], [SolveProgress::Error(error), SolveProgress::Done(_)]
+                if error == "Unable to initialize solution: worker process exited with exit status: 0"),
+            "got {:?}",
+            events
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn worker_exceeding_the_wall_time_limit_is_killed() {
+        let lines = [json(&SolveProgress::Start(year_day(), String::new())).to_string()];
+        let worker = fake_worker(r#"printf '%s\n' "$@"; exec sleep 10"#, &lines);
+        let limits = ProcessLimits {
+            wall_time_limit: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let events = forward(worker, limits);
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(
+            matches!(&events[..], [
+                SolveProgress::Start(..),
+                SolveProgress::Timeout(timeout),
+                SolveProgress::Done(_)
+            ] if timeout.part.is_none() && timeout.value == Duration::from_millis(100)),
+            "got {:?}",
+            events
+        );
+    }
+}
@@ -0,0 +1,360 @@
+use aoc::{
+    answers::Verdict, core::solution_runner::*, inputs::DEFAULT_PROFILE, solution::SolutionType,
+    solutions, util::*,
+};
+use clap::ValueEnum;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    time::Duration,
+};
+
+/// Output of the solve command.
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Progress and results for humans.
+    #[default]
+    Text,
+    Json,
+    Csv,
+    Markdown,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    #[default]
+    Ok,
+    /// An answer differs from the confirmed one.
+    Wrong,
+    Error,
+    Timeout,
+    Cancelled,
+}
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Wrong => "wrong",
+            Status::Error => "error",
+            Status::Timeout => "timeout",
+            Status::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Results of a single day, flat so it can be written as a CSV row as well.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct DayReport {
+    pub year: i32,
+    pub day: u32,
+    pub title: String,
+    pub variant: String,
+    pub profile: String,
+    pub status: Status,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+    pub init_ms: f64,
+    pub part1_ms: Option<f64>,
+    pub part2_ms: Option<f64>,
+    pub total_ms: f64,
+    pub error: Option<String>,
+}
+impl DayReport {
+    fn fail(&mut self, status: Status, error: String) {
+        self.status = status;
+        self.error = Some(error);
+    }
+}
+
+/// Reports of the days by their first event.
+struct Reports {
+    reports: BTreeMap<YearDay, DayReport>,
+    solutions: HashMap<YearDay, Vec<SolutionType>>,
+    variant: Option<String>,
+    profile: String,
+}
+impl Reports {
+    fn get(&mut self, year_day: YearDay) -> &mut DayReport {
+        let info = self
+            .solutions
+            .get(&year_day)
+            .and_then(|s| select_variant(s, self.variant.as_deref()))
+            .map(|s| &s.info);
+        self.reports.entry(year_day).or_insert_with(|| DayReport {
+            year: year_day.year,
+            day: year_day.day,
+            title: info.map(|i| i.title.clone()).unwrap_or_default(),
+            variant: info.map(|i| i.variant.clone()).unwrap_or_default(),
+            profile: self.profile.clone(),
+            ..Default::default()
+        })
+    }
+}
+
+/// Collects the results of the days sent over the stream until it is closed, without printing progress.
+/// The stream should come from [`run_many`], as errors are attributed to their day by its summary.
+pub fn collect_reports(mut rx: ProgressReceiver, options: &SolveOptions) -> Vec<DayReport> {
+    let mut reports = Reports {
+        reports: BTreeMap::new(),
+        solutions: solutions::create_map(),
+        variant: options.variant.clone(),
+        profile: options.profile.clone(),
+    };
+    while let Some(progress) = rx.recv() {
+        match progress {
+            SolveProgress::Start(year_day, _) => _ = reports.get(year_day),
+            SolveProgress::Initialized(p) => reports.get(p.year_day).init_ms = millis(&p.duration),
+            SolveProgress::SuccessResult(p) => {
+                let r = reports.get(p.year_day);
+                let answer = Some(p.value.to_string());
+                let duration = Some(millis(&p.duration));
+                match p.part {
+                    Some(1) => (r.part1, r.part1_ms) = (answer, duration),
+                    _ => (r.part2, r.part2_ms) = (answer, duration),
+                }
+                if let Some(Verdict::Wrong(expected)) = p.verdict {
+                    let error = format!("part {} expected {}", p.part.unwrap(), expected);
+                    r.fail(Status::Wrong, error);
+                }
+            }
+            SolveProgress::ErrorResult(p) => reports.get(p.year_day).fail(
+                Status::Error,
+                format!("part {}: {}", p.part.unwrap(), p.value),
+            ),
+            SolveProgress::Timeout(p) => reports.get(p.year_day).fail(
+                Status::Timeout,
+                format!("exceeded {}", fmt_duration(&p.value)),
+            ),
+            SolveProgress::Cancelled(p) => {
+                let r = reports.get(p.year_day);
+                r.total_ms = millis(&p.duration);
+                r.fail(Status::Cancelled, "cancelled".to_owned());
+            }
+            SolveProgress::Done(p) => reports.get(p.year_day).total_ms = millis(&p.duration),
+            // Errors do not carry their day, which is only known by the summary
+            SolveProgress::Summary(summary) => {
+                for (year_day, _, err) in summary.failures.into_iter().filter(|f| f.1.is_none()) {
+                    let r = reports.get(year_day);
+                    if r.status == Status::Ok {
+                        r.fail(Status::Error, err);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    reports.reports.into_values().collect_vec()
+}
+
+fn millis(duration: &Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+pub fn print_reports(reports: &[DayReport], format: OutputFormat) {
+    match format {
+        OutputFormat::Text => (),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(reports).unwrap()),
+        OutputFormat::Csv => print!("{}", format_csv(reports)),
+        OutputFormat::Markdown => print!("{}", format_markdown(reports)),
+    }
+}
+
+fn format_csv(reports: &[DayReport]) -> String {
+    let escape = |value: &str| match value.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_owned(),
+    };
+    let fmt_ms = |ms: Option<f64>| ms.map(|ms| format!("{:.3}", ms)).unwrap_or_default();
+
+    let mut out = String::new();
+    _ = writeln!(
+        out,
+        "year,day,title,variant,profile,status,part1,part2,init_ms,part1_ms,part2_ms,total_ms,error"
+    );
+    for r in reports {
+        let row = [
+            r.year.to_string(),
+            r.day.to_string(),
+            escape(&r.title),
+            escape(&r.variant),
+            escape(&r.profile),
+            r.status.as_str().to_owned(),
+            escape(r.part1.as_deref().unwrap_or_default()),
+            escape(r.part2.as_deref().unwrap_or_default()),
+            fmt_ms(Some(r.init_ms)),
+            fmt_ms(r.part1_ms),
+            fmt_ms(r.part2_ms),
+            fmt_ms(Some(r.total_ms)),
+            escape(r.error.as_deref().unwrap_or_default()),
+        ];
+        _ = writeln!(out, "{}", row.join(","));
+    }
+
+    out
+}
+
+fn format_markdown(reports: &[DayReport]) -> String {
+    // Multiline answers, like the ones drawn as text, do not fit into a table cell
+    let escape = |value: &str| value.replace('|', "\\|").replace('\n', "<br>");
+    let fmt_ms = |ms: Option<f64>| {
+        ms.map(|ms| fmt_duration(&Duration::from_secs_f64(ms / 1000.0)))
+            .unwrap_or("-".to_owned())
+    };
+
+    // The profile is only shown if there are inputs of several profiles
+    let profiles = reports.iter().any(|r| r.profile != DEFAULT_PROFILE);
+    let profile_cell = |r: &DayReport| match profiles {
+        true => format!(" {} |", escape(&r.profile)),
+        false => String::new(),
+    };
+
+    let mut out = String::new();
+    match profiles {
+        true => {
+            out += "| Day | Title | Profile | Part 1 | Time | Part 2 | Time | Total | Status |\n";
+            out += "|----:|-------|---------|--------|-----:|--------|-----:|------:|--------|\n";
+        }
+        false => {
+            out += "| Day | Title | Part 1 | Time | Part 2 | Time | Total | Status |\n";
+            out += "|----:|-------|--------|-----:|--------|-----:|------:|--------|\n";
+        }
+    }
+    for r in reports {
+        _ = writeln!(
+            out,
+            "| {} | {} |{} {} | {} | {} | {} | {} | {} |",
+            r.day,
+            escape(&r.title),
+            profile_cell(r),
+            escape(r.part1.as_deref().unwrap_or("-")),
+            fmt_ms(r.part1_ms),
+            escape(r.part2.as_deref().unwrap_or("-")),
+            fmt_ms(r.part2_ms),
+            fmt_ms(Some(r.total_ms)),
+            r.status.as_str()
+        );
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::solution::Answer;
+
+    fn grid_report(profile: &str) -> DayReport {
+        let grid = Answer::grid(vec!["#..#".to_owned(), "####".to_owned()], None);
+        DayReport {
+            year: 2022,
+            day: 10,
+            title: "Cathode-Ray \"Tube\", part 1".to_owned(),
+            variant: "default".to_owned(),
+            profile: profile.to_owned(),
+            status: Status::Error,
+            part1: Some(grid.to_string()),
+            part2: None,
+            init_ms: 1.0,
+            part1_ms: Some(2.5),
+            part2_ms: None,
+            total_ms: 3.5,
+            error: Some("part 2: a|b\nc".to_owned()),
+        }
+    }
+
+    fn pack<T>(day: u32, part: Option<u8>, value: T) -> ResultPack<T> {
+        ResultPack {
+            year_day: YearDay::new(2022, day),
+            part,
+            value,
+            duration: Duration::from_millis(2),
+            verdict: None,
+        }
+    }
+
+    #[test]
+    fn errors_are_attributed_to_their_day_by_the_summary() {
+        let failures = vec![(YearDay::new(2022, 2), None, "input not found".to_owned())];
+        let events = [
+            SolveProgress::Start(YearDay::new(2022, 1), String::new()),
+            // The events of concurrent days are interleaved
+            SolveProgress::Error("input not found".to_owned()),
+            SolveProgress::SuccessResult(pack(1, Some(1), Answer::Integer(24000))),
+            SolveProgress::Done(pack(2, None, ())),
+            SolveProgress::SuccessResult(pack(1, Some(2), Answer::Integer(45000))),
+            SolveProgress::Done(pack(1, None, ())),
+            SolveProgress::Summary(SolveSummary {
+                days: 2,
+                solved_parts: 2,
+                failures,
+                ..Default::default()
+            }),
+        ];
+
+        for jobs in [1, 2] {
+            let mut stream = ChannelSyncStream::new();
+            let rx = stream.take_receiver().unwrap();
+            for event in events.iter().cloned() {
+                stream.send(event);
+            }
+            stream.close();
+            let options = SolveOptions {
+                jobs,
+                ..Default::default()
+            };
+            let reports = collect_reports(rx, &options);
+
+            let statuses = reports
+                .iter()
+                .map(|r| (r.day, r.status.as_str(), r.error.as_deref()))
+                .collect_vec();
+            assert_eq!(
+                statuses,
+                [(1, "ok", None), (2, "error", Some("input not found"))]
+            );
+            assert_eq!(reports[0].part2.as_deref(), Some("45000"));
+            assert_eq!(reports[1].total_ms, 2.0);
+        }
+    }
+
+    #[test]
+    fn csv_quotes_values_with_separators() {
+        let csv = format_csv(&[grid_report("a,b")]);
+        let (header, row) = csv.split_once('\n').unwrap();
+        assert!(header.starts_with("year,day,title,"), "{}", header);
+        assert_eq!(
+            row,
+            "2022,10,\"Cathode-Ray \"\"Tube\"\", part 1\",default,\"a,b\",error,\
+             \"#..#\n####\",,1.000,2.500,,3.500,\"part 2: a|b\nc\"\n"
+        );
+    }
+
+    #[test]
+    fn markdown_keeps_multiline_values_in_their_cell() {
+        let ms = |ms: f64| fmt_duration(&Duration::from_secs_f64(ms / 1000.0));
+        let markdown = format_markdown(&[grid_report(DEFAULT_PROFILE)]);
+        let lines = markdown.lines().collect_vec();
+        assert_eq!(lines.len(), 3, "{}", markdown);
+        assert_eq!(
+            lines[2],
+            format!(
+                "| 10 | Cathode-Ray \"Tube\", part 1 | #..#<br>#### | {} | - | - | {} | error |",
+                ms(2.5),
+                ms(3.5)
+            )
+        );
+
+        let markdown = format_markdown(&[grid_report("a|b")]);
+        let lines = markdown.lines().collect_vec();
+        assert!(lines[0].contains("| Profile |"), "{}", lines[0]);
+        assert!(
+            lines[2].starts_with("| 10 | Cathode-Ray \"Tube\", part 1 | a\\|b | #..#<br>#### |"),
+            "{}",
+            lines[2]
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use crate::config::Config;
+use crate::hooks::{self, ScaffoldValues};
 use aoc::core::file_util;
-use aoc::solution::SolutionInfo;
+use aoc::inputs::DEFAULT_PROFILE;
+use aoc::solution::{SolutionInfo, DEFAULT_VARIANT};
 use aoc::solutions;
 use aoc::util::{day_str, GenericResult, MsgError, YearDay};
 use itertools::Itertools;
@@ -16,11 +18,11 @@ use url::Url;
 const CACHE_DIR: &'static str = ".cache";
 const BASE_URL: &'static str = "https://adventofcode.com/";
 
-const SOLUTION_DIR: &'static str = "aoc-lib/src/solutions/";
+pub const SOLUTION_DIR: &'static str = "aoc-lib/src/solutions/";
 const SOLUTION_TEMPLATE_PATH: &'static str =
     "aoc-lib/templates/solution/day__DAY_STR__.rs.template";
 
-const TEST_DIR: &'static str = "aoc-lib/src/tests/";
+pub const TEST_DIR: &'static str = "aoc-lib/src/tests/";
 const TEST_TEMPLATE_PATH: &'static str = "aoc-lib/templates/test/day__DAY_STR___test.rs.template";
 const INPUT_TEMPLATE_PATH: &'static str = "aoc-lib/templates/input/day__DAY_STR__.txt.template";
 
@@ -31,6 +33,8 @@ const DAY_STR_PLACEHOLDER: &'static str = "__DAY_STR__";
 const PUZZLE_INPUT_PLACEHOLDER: &'static str = "__PUZZLE_INPUT__";
 const EXAMPLE_INPUT_PLACEHOLDER: &'static str = "__EXAMPLE_INPUT__";
 const EXAMPLE_PART_1_RESULT_PLACEHOLDER: &'static str = "__EXAMPLE_PART_1_RESULT__";
+/// The editor is opened at the first line containing this in the scaffolded solution.
+const EDITOR_LINE_MARKER: &str = "fn part1";
 
 #[derive(Default)]
 struct PuzzleInfo {
@@ -48,6 +52,7 @@ impl<'a> From<&PuzzleInfo> for SolutionInfo {
             year: p.year,
             day: p.day,
             title: p.title.to_owned(),
+            variant: DEFAULT_VARIANT.to_owned(),
         }
     }
 }
@@ -84,7 +89,11 @@ impl JoinText for scraper::element_ref::Text<'_> {
 pub fn scaffold_inputs(config: &Config) {
     let solutions = solutions::create_map();
     let days = solutions.keys().sorted().collect_vec();
-    println!("Scaffolding inputs for {} days...", solutions.len());
+    println!(
+        "Scaffolding inputs for {} days and {} profiles...",
+        solutions.len(),
+        config.session_keys.len()
+    );
     for (index, k) in days.iter().enumerate() {
         match scaffold_day_internal(
             config,
@@ -118,8 +127,13 @@ fn scaffold_day_internal(
     day: u32,
     scaffold_config: ScaffoldConfig,
 ) -> GenericResult {
-    let session_key = match &config.session_key {
-        Some(key) => key.to_owned(),
+    // The puzzle description is the same for every profile
+    let (profile, session_key) = match config
+        .session_keys
+        .get_key_value(DEFAULT_PROFILE)
+        .or(config.session_keys.iter().next())
+    {
+        Some(profile_key) => profile_key,
         None => {
             println!("Please provide your session key in aoc_config.ini!");
             return Err(MsgError("no session key found").into());
@@ -133,7 +147,7 @@ fn scaffold_day_internal(
         day_str: day_str(day),
         ..Default::default()
     };
-    parse_puzzle_info(&mut puzzle_info, &session_key);
+    parse_puzzle_info(&mut puzzle_info, profile, session_key);
 
     let get_dir = |base_dir: &str| -> String {
         PathBuf::from_iter([base_dir, &file_util::year_directory_name(year)])
@@ -145,55 +159,74 @@ fn scaffold_day_internal(
     let solution_dir = get_dir(SOLUTION_DIR);
     let test_dir = get_dir(TEST_DIR);
 
-    let fs = match scaffold_config.solution {
-        true => Some(generate_file(&puzzle_info, SOLUTION_TEMPLATE_PATH, &solution_dir).unwrap()),
-        false => None,
-    };
-    let ft = match scaffold_config.test {
-        true => Some(generate_file(&puzzle_info, TEST_TEMPLATE_PATH, &test_dir).unwrap()),
-        false => None,
+    // Paths of the files to scaffold in the order they are opened, ending with the solution
+    let mut files = Vec::new();
+    if scaffold_config.input {
+        for profile in config.session_keys.keys() {
+            files.push(file_util::input_file_path(&(&puzzle_info).into(), profile));
+        }
+    }
+    if scaffold_config.test {
+        files.push(target_file_path(
+            &puzzle_info,
+            TEST_TEMPLATE_PATH,
+            &test_dir,
+        )?);
+    }
+    if scaffold_config.solution {
+        files.push(target_file_path(
+            &puzzle_info,
+            SOLUTION_TEMPLATE_PATH,
+            &solution_dir,
+        )?);
+    }
+    let mut values = ScaffoldValues {
+        year,
+        day,
+        files,
+        line: 1,
     };
-    let fi = match scaffold_config.input {
-        true => {
+    if let Some(hook) = &config.pre_scaffold_hook {
+        println!("Running pre-scaffold hook...");
+        if let Err(err) = hooks::run_shell(&values.expand(hook)) {
+            println!("Pre-scaffold hook failed: {}", err);
+            return Err(err);
+        }
+    }
+
+    if scaffold_config.solution {
+        generate_file(&puzzle_info, SOLUTION_TEMPLATE_PATH, &solution_dir)?;
+    }
+    if scaffold_config.test {
+        generate_file(&puzzle_info, TEST_TEMPLATE_PATH, &test_dir)?;
+    }
+    if scaffold_config.input {
+        for (profile, session_key) in &config.session_keys {
+            let input_url = format!("{}/day/{}/input", year, day);
+            puzzle_info.puzzle_input =
+                request_cached(&input_url, profile, session_key).unwrap_or(String::default());
             if puzzle_info.puzzle_input.len() == 0 {
-                println!("Could not scaffold input, check session key in aoc_config.ini!");
+                println!(
+                    "Could not scaffold input of profile '{}', check session key in aoc_config.ini!",
+                    profile
+                );
                 return Err(MsgError("empty input").into());
-            } else {
-                Some(
-                    generate_file(
-                        &puzzle_info,
-                        INPUT_TEMPLATE_PATH,
-                        PathBuf::from(file_util::input_file_path(&(&puzzle_info).into()))
-                            .parent()
-                            .unwrap()
-                            .to_str()
-                            .unwrap(),
-                    )
-                    .unwrap(),
-                )
             }
-        }
-        false => None,
-    };
-
-    let files_to_open = [fi, ft, fs]
-        .into_iter()
-        .filter_map(|x| x)
-        .collect::<Vec<_>>();
-    if let Some(editor_name) = &config.editor_after_scaffold {
-        if scaffold_config.open {
-            let args = ["/C", &editor_name]
-                .into_iter()
-                .map(|x| x.to_owned())
-                .chain(files_to_open.into_iter());
-            println!("Opening scaffolded files in {}...", editor_name);
-            Command::new("cmd")
-                .args(args)
-                .output()
-                .expect("open scaffolded files in editor");
+            generate_input_file(&puzzle_info, profile)?;
         }
     }
 
+    values.line = values
+        .files
+        .last()
+        .and_then(|file| fs::read_to_string(file).ok())
+        .and_then(|contents| {
+            contents
+                .lines()
+                .position(|l| l.contains(EDITOR_LINE_MARKER))
+        })
+        .map_or(1, |index| index + 1);
+
     if scaffold_config.build {
         println!("Re-building to generate indexes...");
         Command::new("cargo")
@@ -202,16 +235,30 @@ fn scaffold_day_internal(
             .expect("builds without errors");
     }
 
+    if let Some(hook) = &config.post_scaffold_hook {
+        println!("Running post-scaffold hook...");
+        if let Err(err) = hooks::run_shell(&values.expand(hook)) {
+            println!("Post-scaffold hook failed: {}", err);
+            return Err(err);
+        }
+    }
+
+    // Opened last, as the editor command may block until the editor is closed
+    if let Some(editor) = hooks::editor_command(config).filter(|_| scaffold_config.open) {
+        let command = values.expand(&editor);
+        println!("Opening scaffolded files: {}", command);
+        if let Err(err) = hooks::run_shell(&command) {
+            println!("Could not open the scaffolded files: {}", err);
+        }
+    }
+
     println!("Ok.");
     Ok(())
 }
 
-fn parse_puzzle_info(puzzle_info: &mut PuzzleInfo, session_key: &str) {
-    let input_url = format!("{}/day/{}/input", puzzle_info.year, puzzle_info.day);
-    puzzle_info.puzzle_input = request_cached(&input_url, session_key).unwrap_or(String::default());
-
+fn parse_puzzle_info(puzzle_info: &mut PuzzleInfo, profile: &str, session_key: &str) {
     let description_url = format!("{}/day/{}", puzzle_info.year, puzzle_info.day);
-    let html = request_cached(&description_url, session_key).unwrap_or(String::default());
+    let html = request_cached(&description_url, profile, session_key).unwrap_or(String::default());
     let html = Html::parse_document(&html);
 
     let title_re = Regex::new(r".*: (.*) ---").unwrap();
@@ -261,8 +308,12 @@ fn parse_puzzle_info(puzzle_info: &mut PuzzleInfo, session_key: &str) {
         .map_or(String::default(), |x| x.trim().to_owned());
 }
 
-fn request_cached(sub_url: &str, session_key: &str) -> GenericResult<String> {
-    let cached_file_name = format!("{}.txt", sub_url.replace("/", "_"));
+fn request_cached(sub_url: &str, profile: &str, session_key: &str) -> GenericResult<String> {
+    // Responses depend on the session key, so the ones of each profile are cached separately
+    let cached_file_name = match profile {
+        DEFAULT_PROFILE => format!("{}.txt", sub_url.replace("/", "_")),
+        profile => format!("{}_{}.txt", sub_url.replace("/", "_"), profile),
+    };
     let cached_file_path = Path::new(CACHE_DIR).join(cached_file_name);
     let url = Url::parse(BASE_URL)?.join(sub_url)?;
     if let Ok(s) = fs::read_to_string(&cached_file_path) {
@@ -316,11 +367,28 @@ fn generate_file(
     Ok(path.to_str().unwrap().to_owned())
 }
 
-fn create_file(
+/// Generates the input file of the profile, which is placed differently than the other scaffolded files.
+fn generate_input_file(puzzle_info: &PuzzleInfo, profile: &str) -> GenericResult<String> {
+    let mut contents = fs::read_to_string(INPUT_TEMPLATE_PATH)?;
+    replace_placeholders(&mut contents, puzzle_info);
+
+    let target_file_path = PathBuf::from(file_util::input_file_path(&puzzle_info.into(), profile));
+    println!("Scaffolding: {}", target_file_path.to_str().unwrap());
+    fs::create_dir_all(
+        target_file_path
+            .parent()
+            .ok_or(MsgError("create directory for file"))?,
+    )?;
+    fs::write(&target_file_path, contents)?;
+    Ok(target_file_path.to_str().unwrap().to_owned())
+}
+
+/// Returns the path of the file generated from the template into the target directory.
+fn target_file_path(
     puzzle_info: &PuzzleInfo,
     template_path: &str,
     target_dir: &str,
-) -> GenericResult<(File, PathBuf)> {
+) -> GenericResult<String> {
     let target_file_name = match Path::new(replace_placeholder(
         &mut template_path.to_owned(),
         DAY_STR_PLACEHOLDER,
@@ -333,7 +401,19 @@ fn create_file(
         None => return Err(MsgError("Target path invalid").into()),
     };
 
-    let target_file_path = Path::new(target_dir).join(&target_file_name);
+    Ok(Path::new(target_dir)
+        .join(&target_file_name)
+        .to_str()
+        .unwrap()
+        .to_owned())
+}
+
+fn create_file(
+    puzzle_info: &PuzzleInfo,
+    template_path: &str,
+    target_dir: &str,
+) -> GenericResult<(File, PathBuf)> {
+    let target_file_path = PathBuf::from(target_file_path(puzzle_info, template_path, target_dir)?);
     println!("Scaffolding: {}", target_file_path.to_str().unwrap());
     fs::create_dir_all(
         target_file_path
@@ -0,0 +1,345 @@
+use crate::isolate::{self, ProcessLimits};
+use aoc::{
+    core::solution_runner::*,
+    solution::CancellationToken,
+    solutions,
+    util::{GenericResult, YearDay},
+};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{self, BufRead, Write},
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+/// Request of a client, in the style of the commands sent to the web worker.
+#[derive(Serialize, Deserialize)]
+pub enum ServeRequest {
+    /// Solves a single day with the default or the given input.
+    StartDay(YearDay, Input),
+    /// Solves the days in order, followed by a summary.
+    StartDays(Vec<YearDay>),
+    /// Cancels the running days.
+    Cancel,
+    /// Lists the implemented solutions.
+    ListSolutions,
+}
+
+/// Message sent to a client besides the [`SolveProgress`] events of the running days.
+#[derive(Serialize, Deserialize)]
+pub enum ServeResponse {
+    Solutions(Vec<SolutionListing>),
+    /// The request could not be parsed or carried out.
+    RequestError(String),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SolutionListing {
+    pub year_day: YearDay,
+    pub title: String,
+    pub variants: Vec<String>,
+}
+
+/// Writes a serialized message to the client.
+pub type Emit = Arc<dyn Fn(String) + Send + Sync>;
+
+/// Days solved for a client, with their events forwarded until the stream is closed.
+struct Run {
+    cancellation_token: CancellationToken,
+    forwarder: JoinHandle<()>,
+}
+
+/// Handles the requests of a client, running one request at a time.
+pub struct Session {
+    options: SolveOptions,
+    /// Limits of the processes solving the days, or None to solve them in the current process.
+    isolation: Option<ProcessLimits>,
+    emit: Emit,
+    run: Option<Run>,
+}
+impl Session {
+    pub fn new(options: SolveOptions, isolation: Option<ProcessLimits>, emit: Emit) -> Session {
+        Session {
+            options,
+            isolation,
+            emit,
+            run: None,
+        }
+    }
+
+    /// Parses and handles a request, reporting invalid ones to the client.
+    pub fn handle_json(&mut self, json: &str) {
+        match serde_json::from_str(json) {
+            Ok(request) => self.handle(request),
+            Err(err) => self.respond(ServeResponse::RequestError(format!(
+                "invalid request: {}",
+                err
+            ))),
+        }
+    }
+
+    pub fn handle(&mut self, request: ServeRequest) {
+        match request {
+            ServeRequest::StartDay(year_day, input) => {
+                self.start(|solver| solver.run(year_day, input))
+            }
+            ServeRequest::StartDays(days) => self.start(|solver| solver.run_many(days)),
+            ServeRequest::Cancel => match &self.run {
+                Some(run) => run.cancellation_token.cancel(),
+                None => self.respond(ServeResponse::RequestError(
+                    "no days are running".to_owned(),
+                )),
+            },
+            ServeRequest::ListSolutions => self.respond(ServeResponse::Solutions(list_solutions())),
+        }
+    }
+
+    /// Starts solving unless days are already running, forwarding the events on a separate thread.
+    fn start<F>(&mut self, run: F)
+    where
+        F: FnOnce(&dyn SolutionRunner<ChannelSyncStream>) -> Arc<Mutex<ChannelSyncStream>>,
+    {
+        if self.is_running() {
+            return self.respond(ServeResponse::RequestError(
+                "days are already running, cancel them first".to_owned(),
+            ));
+        }
+
+        let (cancellation_token, mut rx) = start_run(&self.options, &self.isolation, run);
+        let emit = Arc::clone(&self.emit);
+        let forwarder = thread::spawn(move || {
+            while let Some(progress) = rx.recv() {
+                emit(serde_json::to_string(&progress).unwrap());
+            }
+        });
+        self.run = Some(Run {
+            cancellation_token,
+            forwarder,
+        });
+    }
+
+    fn is_running(&self) -> bool {
+        self.run
+            .as_ref()
+            .is_some_and(|run| !run.forwarder.is_finished())
+    }
+
+    fn respond(&self, response: ServeResponse) {
+        (self.emit)(serde_json::to_string(&response).unwrap());
+    }
+
+    /// Waits for the running days to finish and their events to be sent.
+    pub fn close(&mut self) {
+        if let Some(run) = self.run.take() {
+            _ = run.forwarder.join();
+        }
+    }
+}
+
+/// Returns the implemented solutions ordered by day.
+pub fn list_solutions() -> Vec<SolutionListing> {
+    solutions::create_map()
+        .into_iter()
+        .sorted_by_key(|(year_day, _)| *year_day)
+        .map(|(year_day, types)| SolutionListing {
+            year_day,
+            title: types[0].info.title.clone(),
+            variants: types.iter().map(|t| t.info.variant.clone()).collect_vec(),
+        })
+        .collect_vec()
+}
+
+/// Starts solving on a new thread, returning the token to cancel it and the receiver of its events.
+pub fn start_run<F>(
+    options: &SolveOptions,
+    isolation: &Option<ProcessLimits>,
+    run: F,
+) -> (CancellationToken, ProgressReceiver)
+where
+    F: FnOnce(&dyn SolutionRunner<ChannelSyncStream>) -> Arc<Mutex<ChannelSyncStream>>,
+{
+    // Each run gets its own token, as a cancelled token cannot be reset
+    let options = SolveOptions {
+        cancellation_token: CancellationToken::new(),
+        ..options.clone()
+    };
+    let cancellation_token = options.cancellation_token.clone();
+    let solver = isolate::solution_runner(options, isolation.clone());
+    let stream = run(solver.as_ref());
+    let rx = stream
+        .lock()
+        .unwrap()
+        .take_receiver()
+        .expect("receiver of a new stream should be available");
+
+    (cancellation_token, rx)
+}
+
+/// Reads requests from the standard input and writes the events to the standard output, one JSON per line.
+/// Returns when the standard input is closed and the running days have finished.
+pub fn serve_stdio(options: SolveOptions, isolation: Option<ProcessLimits>) -> GenericResult {
+    let emit: Emit = Arc::new(|line| {
+        let mut stdout = io::stdout().lock();
+        _ = writeln!(stdout, "{}", line);
+        _ = stdout.flush();
+    });
+    let mut session = Session::new(options, isolation, emit);
+    serve_lines(io::stdin().lock(), &mut session)
+}
+
+/// Handles the requests read from the input, one JSON per line.
+/// Returns when the input is closed and the running days have finished.
+pub fn serve_lines(input: impl BufRead, session: &mut Session) -> GenericResult {
+    for line in input.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            session.handle_json(&line);
+        }
+    }
+    session.close();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::core::solution_runner::Input;
+    use serde_json::Value;
+    use std::{io::Cursor, sync::mpsc, time::Duration};
+
+    const DAY1_INPUT: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+    const DAY19_BLUEPRINT: &str =
+        "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. \
+        Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.";
+
+    /// Session writing its messages to memory, one JSON per line.
+    fn session(options: SolveOptions) -> (Session, Arc<Mutex<Vec<u8>>>) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let writer = Arc::clone(&output);
+        let emit: Emit = Arc::new(move |line| {
+            _ = writeln!(writer.lock().unwrap(), "{}", line);
+        });
+        (Session::new(options, None, emit), output)
+    }
+
+    fn messages(output: &Mutex<Vec<u8>>) -> Vec<Value> {
+        let output = output.lock().unwrap();
+        output
+            .lines()
+            .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+            .collect_vec()
+    }
+
+    fn kinds(messages: &[Value]) -> Vec<&str> {
+        messages
+            .iter()
+            .map(|m| m.as_object().unwrap().keys().next().unwrap().as_str())
+            .collect_vec()
+    }
+
+    fn request_line(request: ServeRequest) -> String {
+        serde_json::to_string(&request).unwrap() + "\n"
+    }
+
+    #[test]
+    fn requests_are_served_line_by_line() {
+        let day = YearDay::new(2022, 1);
+        let input = request_line(ServeRequest::StartDay(
+            day,
+            Input::Custom(DAY1_INPUT.to_owned()),
+        )) + "\n"
+            + &request_line(ServeRequest::ListSolutions);
+        let (mut session, output) = session(Default::default());
+        serve_lines(Cursor::new(input), &mut session).unwrap();
+
+        let messages = messages(&output);
+        let answers = messages
+            .iter()
+            .filter_map(|m| m.get("SuccessResult"))
+            .map(|p| p["value"].clone())
+            .collect_vec();
+        assert_eq!(
+            answers,
+            [24000, 45000].map(|x| serde_json::json!({ "Integer": x }))
+        );
+        assert_eq!(kinds(&messages).iter().filter(|k| **k == "Done").count(), 1);
+        let solutions = messages.iter().find_map(|m| m.get("Solutions")).unwrap();
+        let day = serde_json::to_value(day).unwrap();
+        assert!(solutions
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|s| s["year_day"] == day && s["title"] == "Calorie Counting"));
+    }
+
+    #[test]
+    fn malformed_line_is_reported_and_skipped() {
+        let input = "{\"StartDay\": \n".to_owned() + &request_line(ServeRequest::Cancel);
+        let (mut session, output) = session(Default::default());
+        serve_lines(Cursor::new(input), &mut session).unwrap();
+
+        let messages = messages(&output);
+        let errors = messages
+            .iter()
+            .map(|m| m["RequestError"].as_str().unwrap())
+            .collect_vec();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].starts_with("invalid request: "), "{}", errors[0]);
+        assert_eq!(errors[1], "no days are running");
+    }
+
+    #[test]
+    fn unknown_day_finishes_with_an_error() {
+        let input = request_line(ServeRequest::StartDay(
+            YearDay::new(2022, 30),
+            Input::Custom(String::new()),
+        ));
+        let (mut session, output) = session(Default::default());
+        serve_lines(Cursor::new(input), &mut session).unwrap();
+
+        let messages = messages(&output);
+        assert_eq!(kinds(&messages), ["Error", "Done"]);
+        assert_eq!(messages[0]["Error"], "solution not found");
+    }
+
+    #[test]
+    fn cancel_stops_the_running_day() {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let (progress_tx, progress_rx) = mpsc::channel();
+        let writer = Arc::clone(&output);
+        let emit: Emit = Arc::new(move |line| {
+            if line.starts_with("{\"Progress\"") {
+                _ = progress_tx.send(());
+            }
+            _ = writeln!(writer.lock().unwrap(), "{}", line);
+        });
+        let options = SolveOptions {
+            fps: 1000.0,
+            ..Default::default()
+        };
+        let mut session = Session::new(options, None, emit);
+
+        // Slow enough to still be running when cancelled
+        let input = vec![DAY19_BLUEPRINT; 500].join("\n");
+        let start = request_line(ServeRequest::StartDay(
+            YearDay::new(2022, 19),
+            Input::Custom(input),
+        ));
+        session.handle_json(&start);
+        progress_rx.recv_timeout(Duration::from_secs(60)).unwrap();
+        session.handle_json(&start);
+        session.handle_json(&request_line(ServeRequest::Cancel));
+        session.close();
+
+        let messages = messages(&output);
+        let kinds = kinds(&messages);
+        assert_eq!(kinds.first(), Some(&"Start"));
+        assert_eq!(kinds.last(), Some(&"Cancelled"));
+        assert!(!kinds.contains(&"SuccessResult"), "{:?}", kinds);
+        assert!(messages
+            .iter()
+            .any(|m| m["RequestError"] == "days are already running, cancel them first"));
+    }
+}
@@ -1,44 +1,421 @@
 use crate::config::Config;
-use aoc::{core::solution_runner::*, helpers::*, solution::*, solutions, util::*};
+use crate::isolate::{self, ProcessLimits};
+use crate::report::{self, DayReport};
+use aoc::{
+    answers::Verdict,
+    core::{recording::RecordingSolutionRunner, solution_runner::*},
+    inputs,
+    solution::*,
+    solutions,
+    util::*,
+};
 use arboard::Clipboard;
 use itertools::Itertools;
 use std::{
-    collections::HashMap,
     io::{self, Write},
-    thread,
-    time::{Duration, SystemTime},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
 };
 
-pub fn run_all_solutions(config: &Config) -> GenericResult<Duration> {
-    let solutions = solutions::create_map();
-    let solutions_by_year: HashMap<i32, Vec<&SolutionType>> =
-        solutions.iter().fold(HashMap::new(), |mut map, (k, v)| {
-            map.entry(k.year).or_insert_with(|| Vec::new()).extend(v);
-            map
-        });
-    let sorted_solutions = solutions_by_year
-        .into_iter()
-        .sorted_by(|(a, _), (b, _)| a.cmp(b))
-        .map(|(y, days)| (y, days.into_sorted_by(|a, b| a.info.day.cmp(&b.info.day))));
+const MAX_FRAME_HEIGHT: usize = 50;
+const MAX_FRAME_WIDTH: usize = 160;
+
+/// Selects how the days are solved.
+#[derive(Clone, Default)]
+pub struct RunnerOptions {
+    /// Limits of the processes solving the days, or None to solve them in the current process.
+    pub isolation: Option<ProcessLimits>,
+    /// Path the events of the run are recorded to, see [`RecordingSolutionRunner`].
+    pub record: Option<PathBuf>,
+}
+impl RunnerOptions {
+    pub fn create(&self, options: &SolveOptions) -> Box<dyn SolutionRunner<ChannelSyncStream>> {
+        let runner = isolate::solution_runner(options.clone(), self.isolation.clone());
+        match &self.record {
+            Some(path) => Box::new(RecordingSolutionRunner {
+                runner,
+                path: path.clone(),
+            }),
+            None => runner,
+        }
+    }
+}
+
+/// Outcome of a single solution run, used for summaries and comparisons.
+#[derive(Default)]
+pub struct SolveResult {
+    pub variant: String,
+    pub parts: Vec<PartResult>,
+    /// Time spent on initialization, including parsing the input.
+    pub init_duration: Duration,
+    pub duration: Duration,
+}
+
+pub struct PartResult {
+    pub part: u8,
+    pub value: String,
+    pub duration: Duration,
+}
+
+pub fn run_all_solutions(
+    config: &Config,
+    options: &SolveOptions,
+    runner: &RunnerOptions,
+) -> GenericResult<Duration> {
+    let days_by_year = solutions::create_map()
+        .into_keys()
+        .sorted()
+        .group_by(|yd| yd.year);
 
     let mut total_duration = Duration::default();
-    for (year, solutions) in sorted_solutions {
+    for (year, days) in &days_by_year {
         println!("\n--- Year {} ---", year);
-        for day in solutions {
-            total_duration += run_solution_internal(config, day)?;
+        match run_days(config, days.collect_vec(), options, runner) {
+            Some(summary) => total_duration += summary.duration,
+            None => return Ok(total_duration),
         }
     }
 
     Ok(total_duration)
 }
 
-pub fn run_solution(config: &Config, year: i32, day: u32) -> GenericResult<Duration> {
+/// Runs the given days in order, printing their progress and results.
+/// Returns the summary of the run, or None if it was cancelled.
+pub fn run_days(
+    config: &Config,
+    days: Vec<YearDay>,
+    options: &SolveOptions,
+    runner: &RunnerOptions,
+) -> Option<SolveSummary> {
+    let solver = runner.create(options);
+    if options.jobs > 1 {
+        let rx = receiver(solver.run_many(days.clone()));
+        return print_parallel_progress(rx, &days);
+    }
+    let rx = receiver(solver.run_many(days));
+    let (_, summary) = print_progress(rx, config, options);
+    summary
+}
+
+/// Runs the given days with the input of each profile in turn, printing their progress and results.
+/// Returns the summary of each profile, or None if it was cancelled.
+pub fn run_all_profiles(
+    config: &Config,
+    days: Vec<YearDay>,
+    options: &SolveOptions,
+    runner: &RunnerOptions,
+) -> GenericResult<Option<Vec<(String, SolveSummary)>>> {
+    let mut summaries = Vec::new();
+    for (profile, days) in profile_days(&days, options)? {
+        println!("\n--- Profile {} ---", profile);
+        let options = SolveOptions {
+            profile: profile.clone(),
+            ..options.clone()
+        };
+        match run_days(config, days, &options, runner) {
+            Some(summary) => summaries.push((profile, summary)),
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(summaries))
+}
+
+/// Runs the given days with the input of each profile without printing their progress, and returns their results.
+pub fn collect_all_profiles(
+    days: Vec<YearDay>,
+    options: &SolveOptions,
+    runner: &RunnerOptions,
+) -> GenericResult<Vec<DayReport>> {
+    let mut reports = Vec::new();
+    for (profile, days) in profile_days(&days, options)? {
+        let options = SolveOptions {
+            profile,
+            ..options.clone()
+        };
+        reports.extend(collect_days(days, &options, runner));
+        if options.cancellation_token.is_cancelled() {
+            break;
+        }
+    }
+
+    Ok(reports)
+}
+
+/// Returns the profiles having an input for any of the days, each with the days it has an input for.
+fn profile_days(
+    days: &[YearDay],
+    options: &SolveOptions,
+) -> GenericResult<Vec<(String, Vec<YearDay>)>> {
+    let mut profile_days: Vec<(String, Vec<YearDay>)> = Vec::new();
+    for year_day in days {
+        for profile in inputs::resolve_profiles(&options.input_providers, year_day)? {
+            match profile_days.iter_mut().find(|(p, _)| *p == profile) {
+                Some((_, days)) => days.push(*year_day),
+                None => profile_days.push((profile, vec![*year_day])),
+            }
+        }
+    }
+    if profile_days.is_empty() {
+        Err("no input found for any profile")?
+    }
+
+    // Keep the default profile first
+    profile_days.sort_by_key(|(p, _)| (p != inputs::DEFAULT_PROFILE, p.to_owned()));
+    Ok(profile_days)
+}
+
+fn receiver(stream: Arc<Mutex<ChannelSyncStream>>) -> ProgressReceiver {
+    let rx = stream.lock().unwrap().take_receiver();
+    rx.expect("receiver of a new stream should be available")
+}
+
+/// Runs the given days in order without printing their progress, and returns their results.
+pub fn collect_days(
+    days: Vec<YearDay>,
+    options: &SolveOptions,
+    runner: &RunnerOptions,
+) -> Vec<DayReport> {
+    let solver = runner.create(options);
+    report::collect_reports(receiver(solver.run_many(days)), options)
+}
+
+/// Runs every variant of the given day, then prints a table comparing their results.
+pub fn run_all_variants(
+    config: &Config,
+    year: i32,
+    day: u32,
+    options: &SolveOptions,
+    runner: &RunnerOptions,
+) -> GenericResult<Vec<SolveResult>> {
     let solutions = solutions::create_map();
-    let day_type = solutions
+    let day_types = solutions
         .get(&YearDay { year, day })
-        .and_then(|x| x.first())
         .ok_or(MsgError("solution cannot be found"))?;
-    run_solution_internal(config, day_type)
+
+    let mut results = Vec::new();
+    for day_type in day_types {
+        let options = SolveOptions {
+            variant: Some(day_type.info.variant.clone()),
+            ..options.clone()
+        };
+        let solver = runner.create(&options);
+        let rx = receiver(solver.run(YearDay { year, day }, Input::Default));
+        let (day_results, _) = print_progress(rx, config, &options);
+        results.extend(day_results);
+        if options.cancellation_token.is_cancelled() {
+            return Ok(results);
+        }
+    }
+    print_variant_comparison(&results);
+
+    Ok(results)
+}
+
+/// Prints the answers and timings of the variants side by side.
+fn print_variant_comparison(results: &[SolveResult]) {
+    println!("\nVariant comparison:");
+    variant_comparison(results)
+        .iter()
+        .for_each(|line| println!("{}", line));
+}
+
+/// Lines of the table comparing the variants, starting with the header.
+/// Answers differing from the first variant are marked with '*'.
+fn variant_comparison(results: &[SolveResult]) -> Vec<String> {
+    let part_count = results.iter().map(|r| r.parts.len()).max().unwrap_or(0);
+    let header = ["Variant".to_owned(), "Init".to_owned()]
+        .into_iter()
+        .chain((1..=part_count).flat_map(|p| [format!("Part {}", p), "Time".to_owned()]))
+        .chain(["Total".to_owned()])
+        .collect_vec();
+    let rows = results
+        .iter()
+        .map(|r| {
+            let parts = (0..part_count).flat_map(|i| match r.parts.get(i) {
+                Some(p) => {
+                    let value = p.value.lines().next().unwrap_or_default().to_owned();
+                    let differs = results[0].parts.get(i).map(|f| &f.value) != Some(&p.value);
+                    [
+                        format!("{}{}", value, if differs { "*" } else { "" }),
+                        fmt_duration(&p.duration),
+                    ]
+                }
+                None => ["-".to_owned(), "-".to_owned()],
+            });
+            [r.variant.clone(), fmt_duration(&r.init_duration)]
+                .into_iter()
+                .chain(parts)
+                .chain([fmt_duration(&r.duration)])
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let widths = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|r| r[i].chars().count())
+                .chain([header[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect_vec();
+    let format_row = |row: &Vec<String>| {
+        row.iter()
+            .zip(widths.iter())
+            .map(|(cell, &width)| format!("{: <width$}", cell, width = width))
+            .join(" | ")
+            .trim_end()
+            .to_owned()
+    };
+
+    [
+        format_row(&header),
+        widths.iter().map(|&w| "-".repeat(w)).join("-|-"),
+    ]
+    .into_iter()
+    .chain(rows.iter().map(format_row))
+    .collect_vec()
+}
+
+/// State of a day shown as a single row while solving days concurrently.
+struct DayRow {
+    year_day: YearDay,
+    title: String,
+    parts: Vec<String>,
+    status: String,
+}
+impl DayRow {
+    fn line(&self) -> String {
+        let line = [format!("Day {: >2} - {}", self.year_day.day, self.title)]
+            .into_iter()
+            .chain(self.parts.iter().cloned())
+            .chain(Some(self.status.clone()).filter(|s| !s.is_empty()))
+            .join(" | ");
+        line.chars().take(MAX_FRAME_WIDTH).collect()
+    }
+}
+
+/// Prints the progress of concurrently solved days, keeping a live row for each day.
+/// Returns the summary of the run, or None if it was cancelled.
+fn print_parallel_progress(mut rx: ProgressReceiver, days: &[YearDay]) -> Option<SolveSummary> {
+    let mut rows = days
+        .iter()
+        .map(|&year_day| DayRow {
+            year_day,
+            title: String::new(),
+            parts: Vec::new(),
+            status: "Waiting...".to_owned(),
+        })
+        .collect_vec();
+    let mut summary = None;
+    println!();
+    print_rows(&rows, &[], 0);
+    while let Some(items) = rx.recv_items() {
+        // Messages not tied to a row are printed above the rows
+        let mut messages = Vec::new();
+        for progress in items {
+            let year_day = match &progress {
+                SolveProgress::Start(year_day, _) => Some(*year_day),
+                SolveProgress::Initialized(p) => Some(p.year_day),
+                SolveProgress::Progress(p) => Some(p.year_day),
+                SolveProgress::SuccessResult(p) => Some(p.year_day),
+                SolveProgress::ErrorResult(p) => Some(p.year_day),
+                SolveProgress::Timeout(p) => Some(p.year_day),
+                SolveProgress::Cancelled(p) => Some(p.year_day),
+                SolveProgress::Done(p) => Some(p.year_day),
+                _ => None,
+            };
+            let row = match year_day.and_then(|yd| rows.iter_mut().find(|r| r.year_day == yd)) {
+                Some(row) => row,
+                None => {
+                    match progress {
+                        SolveProgress::Error(err) => messages.push(format!("Error: {}", err)),
+                        SolveProgress::Log(p) => messages.push(format!(
+                            "[{}] Day {}: {}",
+                            p.value.level, p.year_day.day, p.value.message
+                        )),
+                        SolveProgress::Summary(s) => summary = Some(s),
+                        _ => (),
+                    }
+                    continue;
+                }
+            };
+            match progress {
+                SolveProgress::Start(_, title) => {
+                    row.title = title;
+                    row.status = "Part 1...".to_owned();
+                }
+                SolveProgress::Progress(p) => {
+                    row.status = format!(
+                        "Part {}... ({}) {:.2}%",
+                        p.part.unwrap(),
+                        fmt_duration(&p.duration),
+                        p.value * 100.0
+                    );
+                }
+                SolveProgress::SuccessResult(p) => {
+                    let value = match &p.value {
+                        Answer::Grid { text, lines } => {
+                            text.clone().or(lines.first().cloned()).unwrap_or_default()
+                        }
+                        value => value.to_string(),
+                    };
+                    row.parts.push(format!(
+                        "Part {}: {}{}",
+                        p.part.unwrap(),
+                        value,
+                        fmt_verdict(&p.verdict)
+                    ));
+                    row.status = match p.part {
+                        Some(1) => "Part 2...".to_owned(),
+                        _ => String::new(),
+                    };
+                }
+                SolveProgress::ErrorResult(p) => {
+                    row.parts
+                        .push(format!("Part {}: Error - {}", p.part.unwrap(), p.value));
+                }
+                SolveProgress::Timeout(p) => match p.part {
+                    Some(part) => row.parts.push(format!("Part {}: Timeout", part)),
+                    None => row.parts.push("Initialization timeout".to_owned()),
+                },
+                SolveProgress::Cancelled(p) => {
+                    row.status = format!("Cancelled after {}", fmt_duration(&p.duration))
+                }
+                SolveProgress::Done(p) => {
+                    row.status = match row.title.is_empty() {
+                        true => "Failed".to_owned(),
+                        false => format!("Runtime: {}", fmt_duration(&p.duration)),
+                    }
+                }
+                _ => (),
+            }
+        }
+        print_rows(&rows, &messages, rows.len());
+    }
+
+    summary
+}
+
+/// Prints the messages followed by the rows, replacing the previously printed rows using ANSI cursor movement.
+fn print_rows(rows: &[DayRow], messages: &[String], prev_row_count: usize) {
+    let mut content = String::new();
+    if prev_row_count > 0 {
+        content += &format!("\x1b[{}A", prev_row_count);
+    }
+    for line in messages
+        .iter()
+        .cloned()
+        .chain(rows.iter().map(|r| r.line()))
+    {
+        content += "\x1b[2K";
+        content += &line;
+        content += "\n";
+    }
+    print!("{}", content);
+    io::stdout().flush().unwrap();
 }
 
 struct HandleProgress;
@@ -49,6 +426,15 @@ impl ProgressHandler for HandleProgress {
     }
 }
 
+/// Describes the comparison with the confirmed answer, or nothing if there is none.
+fn fmt_verdict(verdict: &Option<Verdict>) -> String {
+    match verdict {
+        Some(Verdict::Correct) => " (correct)".to_owned(),
+        Some(Verdict::Wrong(expected)) => format!(" (wrong, expected {})", expected),
+        Some(Verdict::Unknown) | None => String::new(),
+    }
+}
+
 /// Prints over the current line with \r
 fn print(content: &str, prev_line_length: usize) -> usize {
     let spaces = String::from_iter(
@@ -59,6 +445,31 @@ fn print(content: &str, prev_line_length: usize) -> usize {
     content.len()
 }
 
+/// Prints a frame above the current line, replacing the previous frame using ANSI cursor movement.
+/// Returns the number of lines used by the frame.
+fn print_frame(lines: &[String], prev_frame_height: usize) -> usize {
+    let lines = lines
+        .iter()
+        .take(MAX_FRAME_HEIGHT)
+        .map(|l| l.chars().take(MAX_FRAME_WIDTH).collect::<String>())
+        .collect_vec();
+    let height = lines.len().max(prev_frame_height);
+
+    let mut content = String::from("\r\x1b[2K");
+    if prev_frame_height > 0 {
+        content += &format!("\x1b[{}A", prev_frame_height);
+    }
+    for index in 0..height {
+        content += "\x1b[2K";
+        content += lines.get(index).map(|l| l.as_str()).unwrap_or_default();
+        content += "\n";
+    }
+    print!("{}", content);
+    io::stdout().flush().unwrap();
+
+    height
+}
+
 fn print_and_copy(
     part: u32,
     result: &SolveProgress,
@@ -66,16 +477,23 @@ fn print_and_copy(
     config: &Config,
     prev_line_length: usize,
 ) {
-    let mut result_text = match result {
-        SolveProgress::SuccessResult(r) => r.value.clone(),
-        SolveProgress::ErrorResult(r) => format!("Error - {}", &r.value),
+    let (result_text, grid_lines) = match result {
+        SolveProgress::SuccessResult(r) => {
+            let (text, lines) = match &r.value {
+                // Display grid results below the recognized text
+                Answer::Grid { lines, text } => (text.clone().unwrap_or_default(), Some(lines)),
+                value => (value.to_string(), None),
+            };
+            (text + &fmt_verdict(&r.verdict), lines)
+        }
+        SolveProgress::ErrorResult(r) => (format!("Error - {}", &r.value), None),
+        SolveProgress::Timeout(r) => (
+            format!("Timeout - exceeded {}", fmt_duration(&r.value)),
+            None,
+        ),
         _ => panic!("Not supported result!"),
     };
 
-    // Display multiline results in an empty line
-    if result_text.contains("\n") {
-        result_text = format!("\n{}", &result_text);
-    }
     let content = format!(
         "Part {} ({}): {}",
         part,
@@ -83,13 +501,16 @@ fn print_and_copy(
         &result_text
     );
     print(&content, prev_line_length);
-    println!("");
+    println!();
+    for line in grid_lines.into_iter().flatten() {
+        println!("{}", line);
+    }
 
     if config.copy_result_to_clipboard {
-        if let SolveProgress::SuccessResult(_) = &result {
+        if let SolveProgress::SuccessResult(r) = &result {
             match Clipboard::new() {
                 Ok(mut clipboard) => {
-                    if let Err(err) = clipboard.set_text(result_text) {
+                    if let Err(err) = clipboard.set_text(r.value.to_string()) {
                         println!("Warning: could not copy output to clipboard! {}", err);
                     }
                 }
@@ -99,90 +520,232 @@ fn print_and_copy(
     };
 }
 
-fn run_solution_internal(config: &Config, day_type: &SolutionType) -> GenericResult<Duration> {
-    let SolutionInfo { year, day, .. } = day_type.info;
-    let year_day = YearDay::new(year, day);
-    println!("\nDay {} - {}", day_type.info.day, day_type.info.title);
-
-    let solver = ThreadSolutionRunner {};
-    let stream = solver.run(year_day, Input::Default);
-
-    let config = config.clone();
-    let t = thread::spawn(move || {
-        let mut _dbg_loop_count = 0;
-        let mut _dbg_lock_duration = Duration::default();
-        let mut _dbg_sleep_duration = Duration::default();
-
-        let mut prev_line_length = print("Part 1...", 0);
-        let mut solution_duration = Duration::default();
-        loop {
-            _dbg_loop_count += 1;
-            let before_lock = SystemTime::now();
-            // If we lock in 'while let' or 'match' instead, we would lock while sleeping
-            let next_items = stream.lock().unwrap().next_items();
-            _dbg_lock_duration += before_lock.elapsed().unwrap_or_default();
-
-            let items = match next_items {
-                Some(items) if items.len() > 0 => items,
-                Some(_) => {
-                    let before_sleep = SystemTime::now();
-                    thread::sleep(Duration::from_millis(10));
-                    _dbg_sleep_duration += before_sleep.elapsed().unwrap_or_default();
-                    continue;
+/// Prints the progress and results of the solutions sent over the stream until it is closed.
+/// Returns the results of the started days and the summary, if the stream ended with one.
+fn print_progress(
+    mut rx: ProgressReceiver,
+    config: &Config,
+    options: &SolveOptions,
+) -> (Vec<SolveResult>, Option<SolveSummary>) {
+    let solutions = solutions::create_map();
+    let mut prev_line_length = 0;
+    // Progress of the running part shown on the current line, redrawn after log messages
+    let mut status = String::new();
+    let mut frame_height = 0;
+    let mut results = Vec::new();
+    let mut current: Option<SolveResult> = None;
+    let mut summary = None;
+    while let Some(items) = rx.recv_items() {
+        for progress in items {
+            // Keep lines printed after a frame instead of overwriting them with the next frame
+            if !matches!(
+                &progress,
+                SolveProgress::Frame(_) | SolveProgress::Progress(_)
+            ) {
+                frame_height = 0;
+            }
+            if !matches!(
+                &progress,
+                SolveProgress::Progress(_) | SolveProgress::Log(_) | SolveProgress::Frame(_)
+            ) {
+                status.clear();
+            }
+            match &progress {
+                SolveProgress::Start(year_day, title) => {
+                    let variant = solutions
+                        .get(year_day)
+                        .and_then(|s| select_variant(s, options.variant.as_deref()))
+                        .map(|s| s.info.variant.clone())
+                        .unwrap_or_default();
+                    match variant.as_str() {
+                        DEFAULT_VARIANT => println!("\nDay {} - {}", year_day.day, title),
+                        variant => {
+                            println!("\nDay {} - {} ({})", year_day.day, title, variant)
+                        }
+                    }
+                    status = "Part 1...".to_owned();
+                    prev_line_length = print(&status, 0);
+                    current = Some(SolveResult {
+                        variant,
+                        ..Default::default()
+                    });
                 }
-                None => break,
-            };
+                SolveProgress::SuccessResult(p) => {
+                    print_and_copy(
+                        p.part.unwrap() as u32,
+                        &progress,
+                        &p.duration,
+                        config,
+                        prev_line_length,
+                    );
+                    if let Some(r) = &mut current {
+                        r.parts.push(PartResult {
+                            part: p.part.unwrap(),
+                            value: p.value.to_string(),
+                            duration: p.duration,
+                        });
+                    }
 
-            for progress in items {
-                match &progress {
-                    SolveProgress::SuccessResult(p) => {
+                    if p.part == Some(1) {
+                        status = "Part 2...".to_owned();
+                        prev_line_length = print(&status, 0);
+                    }
+                }
+                SolveProgress::ErrorResult(p) => {
+                    print_and_copy(
+                        p.part.unwrap() as u32,
+                        &progress,
+                        &p.duration,
+                        config,
+                        prev_line_length,
+                    );
+                    if let Some(r) = &mut current {
+                        r.parts.push(PartResult {
+                            part: p.part.unwrap(),
+                            value: "Error".to_owned(),
+                            duration: p.duration,
+                        });
+                    }
+                }
+                SolveProgress::Done(p) => {
+                    // Days failing before their start have no result
+                    if let Some(mut r) = current.take() {
+                        println!(
+                            "Runtime: {} (init: {})",
+                            fmt_duration(&p.duration),
+                            fmt_duration(&r.init_duration)
+                        );
+                        r.duration = p.duration;
+                        results.push(r);
+                    }
+                }
+                SolveProgress::Timeout(p) => match p.part {
+                    Some(part) => {
                         print_and_copy(
-                            p.part.unwrap() as u32,
+                            part as u32,
                             &progress,
                             &p.duration,
-                            &config,
+                            config,
                             prev_line_length,
                         );
-
-                        if p.part == Some(1) {
-                            prev_line_length = print("Part 2...", 0);
+                        if let Some(r) = &mut current {
+                            r.parts.push(PartResult {
+                                part,
+                                value: "Timeout".to_owned(),
+                                duration: p.duration,
+                            });
                         }
                     }
-                    SolveProgress::ErrorResult(p) => print_and_copy(
-                        p.part.unwrap() as u32,
-                        &progress,
-                        &p.duration,
-                        &config,
-                        prev_line_length,
+                    None => println!(
+                        "\nError: initialization exceeded {}",
+                        fmt_duration(&p.value)
                     ),
-                    SolveProgress::Done(p) => {
-                        println!("Runtime: {}", fmt_duration(&p.duration));
-                        solution_duration = p.duration;
+                },
+                SolveProgress::Cancelled(p) => {
+                    print(
+                        &format!("Cancelled after {}", fmt_duration(&p.duration)),
+                        prev_line_length,
+                    );
+                    println!();
+                    if let Some(mut r) = current.take() {
+                        r.duration = p.duration;
+                        results.push(r);
                     }
-                    SolveProgress::Error(p) => println!("\nError: {}", p),
-                    SolveProgress::Progress(p) => {
-                        prev_line_length = print(
-                            &format!(
-                                "Part {}... ({}) {:.2}%",
-                                p.part.unwrap(),
-                                fmt_duration(&p.duration),
-                                p.value * 100.0
-                            ),
-                            prev_line_length,
-                        );
+                }
+                SolveProgress::Error(p) => println!("\nError: {}", p),
+                SolveProgress::Log(p) => {
+                    // Replace the status line with the message, then redraw it below
+                    println!("\r\x1b[2K[{}] {}", p.value.level, p.value.message);
+                    prev_line_length = print(&status, 0);
+                }
+                SolveProgress::Progress(p) => {
+                    status = format!(
+                        "Part {}... ({}) {:.2}%",
+                        p.part.unwrap(),
+                        fmt_duration(&p.duration),
+                        p.value * 100.0
+                    );
+                    prev_line_length = print(&status, prev_line_length);
+                }
+                SolveProgress::Frame(p) => {
+                    frame_height = print_frame(&p.value, frame_height);
+                    prev_line_length = 0;
+                }
+                SolveProgress::Initialized(p) => {
+                    if let Some(r) = &mut current {
+                        r.init_duration = p.duration;
                     }
-                    SolveProgress::Start(..) => (),
                 }
+                SolveProgress::Summary(s) => summary = Some(s.clone()),
             }
         }
+    }
+
+    (results, summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(variant: &str, answers: &[&str]) -> SolveResult {
+        SolveResult {
+            variant: variant.to_owned(),
+            parts: answers
+                .iter()
+                .enumerate()
+                .map(|(index, answer)| PartResult {
+                    part: index as u8 + 1,
+                    value: answer.to_string(),
+                    duration: Duration::ZERO,
+                })
+                .collect_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Variant and answers of the rows of the comparison table.
+    fn answer_cells(lines: &[String]) -> Vec<Vec<String>> {
+        lines[2..]
+            .iter()
+            .map(|line| {
+                let cells = line.split('|').map(|c| c.trim().to_owned()).collect_vec();
+                [0, 2, 4].iter().map(|&i| cells[i].clone()).collect_vec()
+            })
+            .collect_vec()
+    }
 
-        // println!(
-        //     "loops: {}, lock: {:?}, sleep: {:?}",
-        //     _dbg_loop_count, _dbg_lock_duration, _dbg_sleep_duration
-        // );
-        solution_duration
-    });
+    #[test]
+    fn variant_comparison_marks_answers_differing_from_the_first_variant() {
+        let lines = variant_comparison(&[
+            variant("default", &["1", "2"]),
+            variant("fast", &["1", "3"]),
+            variant("partial", &["1"]),
+        ]);
 
-    let duration = t.join().unwrap();
-    Ok(duration)
+        assert!(lines[0].starts_with("Variant | Init | Part 1 | Time | Part 2 | Time | Total"));
+        assert_eq!(
+            answer_cells(&lines),
+            vec![
+                vec!["default", "1", "2"],
+                vec!["fast", "1", "3*"],
+                vec!["partial", "1", "-"],
+            ]
+        );
+    }
+
+    #[test]
+    fn all_variants_reports_an_unknown_day() {
+        let err = run_all_variants(
+            &Config::default(),
+            1999,
+            1,
+            &SolveOptions::default(),
+            &RunnerOptions::default(),
+        )
+        .err()
+        .unwrap();
+
+        assert_eq!(err.to_string(), "solution cannot be found");
+    }
 }
@@ -0,0 +1,370 @@
+use crate::{
+    report::{self, DayReport, Status},
+    scaffold,
+};
+use aoc::{
+    core::{
+        file_util,
+        hot_reload::HotReloadSolutionRunner,
+        solution_runner::{ChannelSyncStream, Input, SolutionRunner, SolveOptions},
+    },
+    inputs::DEFAULT_PROFILE,
+    util::{day_str, fmt_duration, GenericResult, YearDay},
+};
+use chrono::Local;
+use itertools::Itertools;
+use std::{
+    env::consts::{DLL_PREFIX, DLL_SUFFIX},
+    fs,
+    path::PathBuf,
+    process::{Command, Output},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// Target directory of the builds, so that the binary of the running aoc-cli is not replaced.
+const WATCH_TARGET_DIR: &str = "target/watch";
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Time given to editors to finish saving before rebuilding.
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+/// Maximum number of compiler errors shown for a failed build.
+const MAX_BUILD_ERRORS: usize = 10;
+
+/// Result of a cargo command which needs to build the code first.
+enum Build<T> {
+    Succeeded(T),
+    /// The compiler errors of the failed build.
+    Failed(Vec<String>),
+}
+
+/// Outcome of the example tests of a day.
+#[derive(Default)]
+struct ExampleResults {
+    passed: usize,
+    /// Names of the failed tests with their output.
+    failed: Vec<(String, Vec<String>)>,
+}
+
+/// Returns the solution, test and input files of the day.
+pub fn watched_paths(year_day: &YearDay) -> Vec<PathBuf> {
+    let year_dir = file_util::year_directory_name(year_day.year);
+    let day_str = day_str(year_day.day);
+    vec![
+        PathBuf::from_iter([
+            scaffold::SOLUTION_DIR,
+            &year_dir,
+            &format!("day{}.rs", day_str),
+        ]),
+        PathBuf::from_iter([
+            scaffold::TEST_DIR,
+            &year_dir,
+            &format!("day{}_test.rs", day_str),
+        ]),
+        PathBuf::from_iter([file_util::INPUT_PATH, &year_dir])
+            .join(file_util::input_file_name(year_day.day, DEFAULT_PROFILE)),
+    ]
+}
+
+/// Returns the path of aoc-lib built as a dynamic library by a hot watch.
+pub fn hot_library_path() -> PathBuf {
+    PathBuf::from_iter([WATCH_TARGET_DIR, "debug"]).join(format!("{}aoc{}", DLL_PREFIX, DLL_SUFFIX))
+}
+
+/// Rebuilds the day, runs its example tests and solves its input on every change of its files until Ctrl-C is pressed.
+/// A hot watch solves the input with aoc-lib built as a dynamic library and reloaded by the current process,
+/// instead of running a freshly built aoc-cli.
+pub fn watch_day(year_day: YearDay, hot: bool) -> GenericResult {
+    let paths = watched_paths(&year_day);
+    println!(
+        "Watching day {} of {}, press Ctrl-C to stop:",
+        year_day.day, year_day.year
+    );
+    for path in &paths {
+        println!("  {}", path.display());
+    }
+
+    let mut tracker = ChangeTracker {
+        modified: modified_times(&paths),
+        paths,
+    };
+    check_day(year_day, hot, "Started")?;
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let changed = tracker.changes(modified_times(&tracker.paths));
+        if changed.is_empty() {
+            continue;
+        }
+        // The changes saved while settling belong to the same check
+        thread::sleep(SETTLE_TIME);
+        tracker.changes(modified_times(&tracker.paths));
+        check_day(year_day, hot, &format!("Changed {}", changed.join(", ")))?;
+    }
+}
+
+/// Modification times of the watched files, to tell which of them changed between two polls.
+struct ChangeTracker {
+    paths: Vec<PathBuf>,
+    /// Modification times of the paths at the last poll, None for missing files.
+    modified: Vec<Option<SystemTime>>,
+}
+impl ChangeTracker {
+    /// Returns the names of the files modified, created or removed since the last poll, and remembers the current times.
+    fn changes(&mut self, current: Vec<Option<SystemTime>>) -> Vec<String> {
+        let changed = self
+            .paths
+            .iter()
+            .zip(self.modified.iter().zip(&current))
+            .filter(|(_, (before, after))| before != after)
+            .filter_map(|(path, _)| Some(path.file_name()?.to_str()?.to_owned()))
+            .collect_vec();
+        self.modified = current;
+        changed
+    }
+}
+
+fn modified_times(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect_vec()
+}
+
+/// Runs the example tests, then solves the input if they could be built, printing a summary of both.
+fn check_day(year_day: YearDay, hot: bool, reason: &str) -> GenericResult {
+    println!("\n--- {} {} ---", Local::now().format("%H:%M:%S"), reason);
+    match run_examples(&year_day)? {
+        Build::Succeeded(results) => print_examples(&results),
+        Build::Failed(errors) => {
+            print_build_errors(&errors);
+            return Ok(());
+        }
+    }
+    let result = match hot {
+        true => solve_input_hot(&year_day)?,
+        false => solve_input(&year_day)?,
+    };
+    match result {
+        Build::Succeeded(Some(report)) => print_report(&report),
+        Build::Succeeded(None) => println!("Input: no result"),
+        Build::Failed(errors) => print_build_errors(&errors),
+    }
+
+    Ok(())
+}
+
+/// Runs the example tests of the day with cargo test.
+fn run_examples(year_day: &YearDay) -> GenericResult<Build<ExampleResults>> {
+    let filter = format!(
+        "tests::year{}::day{}_test::example",
+        year_day.year,
+        day_str(year_day.day)
+    );
+    let output = cargo(&["test", "-p", "aoc-lib", "--lib", &filter])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() && !stdout.contains("test result:") {
+        return Ok(Build::Failed(build_errors(&output)));
+    }
+
+    let mut results = ExampleResults::default();
+    let mut lines = stdout.lines().peekable();
+    while let Some(line) = lines.next() {
+        if let Some(test) = line.strip_prefix("test ") {
+            match test.rsplit_once(" ... ") {
+                Some((_, "ok")) => results.passed += 1,
+                Some((name, "FAILED")) => results.failed.push((short_name(name), Vec::new())),
+                _ => (),
+            }
+        } else if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|x| x.strip_suffix(" stdout ----"))
+        {
+            // Output of a failed test, until the next section
+            let output = lines
+                .peeking_take_while(|l| !l.starts_with("----") && *l != "failures:")
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with("thread '") && !l.starts_with("note:"))
+                .map(str::to_owned)
+                .collect_vec();
+            let name = short_name(name);
+            if let Some((_, failure)) = results.failed.iter_mut().find(|(n, _)| *n == name) {
+                *failure = output;
+            }
+        }
+    }
+
+    Ok(Build::Succeeded(results))
+}
+
+/// Solves the day with its default input using the freshly built aoc-cli.
+fn solve_input(year_day: &YearDay) -> GenericResult<Build<Option<DayReport>>> {
+    let (year, day) = (year_day.year.to_string(), year_day.day.to_string());
+    let args = ["run", "-q", "-p", "aoc-cli", "--", "solve", "-f", "json"];
+    let output = cargo(&[&args[..], &["-y", &year, &day]].concat())?;
+    if output.stdout.is_empty() {
+        return Ok(Build::Failed(build_errors(&output)));
+    }
+    let reports: Vec<DayReport> = serde_json::from_slice(&output.stdout)?;
+
+    Ok(Build::Succeeded(reports.into_iter().next()))
+}
+
+/// Solves the day with its default input using aoc-lib freshly built as a dynamic library.
+fn solve_input_hot(year_day: &YearDay) -> GenericResult<Build<Option<DayReport>>> {
+    let args = ["rustc", "-p", "aoc-lib", "--lib", "--crate-type", "dylib"];
+    let output = cargo(&args)?;
+    if !output.status.success() {
+        return Ok(Build::Failed(build_errors(&output)));
+    }
+    let options = SolveOptions::default();
+    let runner = HotReloadSolutionRunner {
+        options: options.clone(),
+        path: hot_library_path(),
+    };
+    let stream: Arc<Mutex<ChannelSyncStream>> = runner.run(*year_day, Input::Default);
+    let rx = stream.lock().unwrap().take_receiver();
+    let reports = report::collect_reports(rx.expect("receiver should be available"), &options);
+
+    Ok(Build::Succeeded(reports.into_iter().next()))
+}
+
+fn cargo(args: &[&str]) -> GenericResult<Output> {
+    let (command, args) = args.split_first().expect("cargo command should be given");
+    let output = Command::new("cargo")
+        .arg(command)
+        .args([
+            "--target-dir",
+            WATCH_TARGET_DIR,
+            "--message-format",
+            "short",
+        ])
+        .args(args)
+        // Keep the output of failed tests short
+        .env("RUST_BACKTRACE", "0")
+        .output()?;
+
+    Ok(output)
+}
+
+/// Returns the compiler errors, or the whole error output if it contains none.
+fn build_errors(output: &Output) -> Vec<String> {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let errors = stderr
+        .lines()
+        .filter(|l| l.contains("error") && !l.starts_with("error: could not compile"))
+        .map(str::to_owned)
+        .collect_vec();
+    match errors.is_empty() {
+        true => stderr.lines().map(str::to_owned).collect_vec(),
+        false => errors,
+    }
+}
+
+/// Name of the test function without its module path.
+fn short_name(name: &str) -> String {
+    name.rsplit("::").next().unwrap_or(name).to_owned()
+}
+
+fn print_build_errors(errors: &[String]) {
+    println!("Build failed:");
+    for error in errors.iter().take(MAX_BUILD_ERRORS) {
+        println!("  {}", error);
+    }
+    if errors.len() > MAX_BUILD_ERRORS {
+        println!("  ... and {} more", errors.len() - MAX_BUILD_ERRORS);
+    }
+}
+
+fn print_examples(results: &ExampleResults) {
+    match (results.passed, results.failed.len()) {
+        (0, 0) => println!("Examples: no tests found"),
+        (passed, 0) => println!("Examples: ok, {} passed", passed),
+        (passed, failed) => println!("Examples: FAILED, {} failed, {} passed", failed, passed),
+    }
+    for (name, output) in &results.failed {
+        println!("  {}:", name);
+        for line in output {
+            println!("    {}", line);
+        }
+    }
+}
+
+fn print_report(report: &DayReport) {
+    let fmt_ms = |ms: f64| fmt_duration(&Duration::from_secs_f64(ms / 1000.0));
+    let parts = [
+        (1, &report.part1, report.part1_ms),
+        (2, &report.part2, report.part2_ms),
+    ];
+    for (part, answer, ms) in parts {
+        if let (Some(answer), Some(ms)) = (answer, ms) {
+            println!("Part {}: {} ({})", part, answer, fmt_ms(ms));
+        }
+    }
+    match (&report.status, &report.error) {
+        (Status::Ok, _) => println!("Input: ok in {}", fmt_ms(report.total_ms)),
+        (status, error) => println!(
+            "Input: {} - {}",
+            status.as_str(),
+            error.as_deref().unwrap_or_default()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(secs: u64) -> Option<SystemTime> {
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    #[test]
+    fn watched_paths_are_the_files_of_the_day() {
+        let paths = watched_paths(&YearDay::new(2022, 5));
+
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from_iter([scaffold::SOLUTION_DIR, "year2022", "day05.rs"]),
+                PathBuf::from_iter([scaffold::TEST_DIR, "year2022", "day05_test.rs"]),
+                PathBuf::from_iter([file_util::INPUT_PATH, "year2022", "day05.txt"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn changes_name_the_modified_created_and_removed_files() {
+        let mut tracker = ChangeTracker {
+            paths: watched_paths(&YearDay::new(2022, 5)),
+            modified: vec![time(1), time(1), None],
+        };
+
+        assert!(tracker.changes(vec![time(1), time(1), None]).is_empty());
+        assert_eq!(tracker.changes(vec![time(2), time(1), None]), ["day05.rs"]);
+        assert_eq!(
+            tracker.changes(vec![time(2), None, time(3)]),
+            ["day05_test.rs", "day05.txt"]
+        );
+        assert!(tracker.changes(vec![time(2), None, time(3)]).is_empty());
+    }
+
+    #[test]
+    fn changes_saved_while_settling_are_checked_once() {
+        let mut tracker = ChangeTracker {
+            paths: watched_paths(&YearDay::new(2022, 5)),
+            modified: vec![time(1), time(1), time(1)],
+        };
+
+        // Poll, then settle while the editor saves the test file too
+        assert_eq!(
+            tracker.changes(vec![time(2), time(1), time(1)]),
+            ["day05.rs"]
+        );
+        tracker.changes(vec![time(2), time(2), time(1)]);
+
+        assert!(
+            tracker.changes(vec![time(2), time(2), time(1)]).is_empty(),
+            "the next poll should not report the settled changes again"
+        );
+    }
+}
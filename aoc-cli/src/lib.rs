@@ -1,15 +1,29 @@
 // Module definitions
 pub mod core {
     pub mod args;
+    pub mod bench;
     pub mod config;
+    pub mod hooks;
+    pub mod http;
+    pub mod isolate;
+    pub mod report;
     pub mod scaffold;
+    pub mod serve;
     pub mod solve;
     pub mod timing;
+    pub mod watch;
 }
 
 // Re-exports
 pub use crate::core::args;
+pub use crate::core::bench;
 pub use crate::core::config;
+pub use crate::core::hooks;
+pub use crate::core::http;
+pub use crate::core::isolate;
+pub use crate::core::report;
 pub use crate::core::scaffold;
+pub use crate::core::serve;
 pub use crate::core::solve;
 pub use crate::core::timing;
+pub use crate::core::watch;
@@ -1,61 +1,234 @@
 use aoc::{
+    answers,
+    core::recording,
+    core::solution_runner::{SolveOptions, SolveSummary},
+    inputs::{self, DirectoryInputs, FileInput, InputProvider, StdinInput},
+    solution::{CancellationToken, LogLevel},
     solutions::{self},
     util::*,
 };
-use aoc_cli::{args::*, config::*, scaffold, solve, timing};
-use aoc_ui;
+use aoc_cli::{
+    args::*,
+    bench::{self, BenchRun},
+    config::*,
+    http, isolate,
+    report::{self, OutputFormat},
+    scaffold, serve,
+    solve::{self, RunnerOptions},
+    timing, watch,
+};
+use aoc_ui::{self, state::RunMode};
 use clap::Parser;
 use itertools::Itertools;
-use std::time::Duration;
+use std::{
+    path::{Path, PathBuf},
+    process,
+    sync::Arc,
+};
 
 fn main() {
+    let args = Args::parse();
+
+    // The worker of an isolated run only talks to its parent, and leaves the configuration alone
+    if let Some(Command::Worker) = args.mode {
+        if let Err(err) = isolate::run_worker() {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+        return;
+    }
+
+    // Keep machine-readable output free of anything else
+    let machine_readable = match &args.mode {
+        Some(Command::Solve { format, .. }) => *format != OutputFormat::Text,
+        Some(Command::Serve { stdio, .. }) => *stdio,
+        _ => false,
+    };
     let config = Config::load_from_file(DEFAULT_CONFIG_PATH)
         .or_else(|_| {
-            println!(
+            let message = format!(
                 "Creating configuration with default values: {}",
                 DEFAULT_CONFIG_PATH
             );
+            match machine_readable {
+                true => eprintln!("{}", message),
+                false => println!("{}", message),
+            }
             let config = Config::default();
             config.save_to_file(DEFAULT_CONFIG_PATH)?;
             Ok::<_, DynError>(config)
         })
         .expect("config loaded or generated");
-    let args = Args::parse();
-
-    aoc_ui::char_image::print_text("AOC 2022", '#', ' ');
-    println!("--- Advent of Code 2022 CLI by sanraith ---");
+    if !machine_readable {
+        aoc_ui::char_image::print_text("AOC 2022", '#', ' ');
+        println!("--- Advent of Code 2022 CLI by sanraith ---");
+    }
 
     match args.mode {
         Some(Command::Scaffold { year, days, inputs }) => scaffold(&config, year, days, inputs),
-        Some(Command::Solve { year, days }) => {
+        Some(Command::Solve {
+            year,
+            days,
+            input,
+            time_limit,
+            variant,
+            all_variants,
+            profile,
+            all_profiles,
+            animate,
+            verbose,
+            jobs,
+            format,
+            isolation,
+            record,
+        }) => {
             let year = year.unwrap_or(timing::latest_aoc_date().year);
-            let mut days = days
-                .into_iter()
-                .map(|day| YearDay::new(year, day))
-                .collect_vec();
-
-            if days.len() == 0 {
-                days.append(
-                    &mut solutions::create_map()
-                        .keys()
-                        .filter(|x| x.year == year)
-                        .sorted()
-                        .map(|x| x.clone())
-                        .collect_vec(),
-                );
-                if days.len() == 0 {
-                    println!("Error: no solution found for {}!", year);
-                    return;
+            let days = match select_days(year, days) {
+                Some(days) => days,
+                None => return,
+            };
+
+            let options = SolveOptions {
+                part_time_limit: time_limit,
+                variant,
+                log_level: log_level_from_verbosity(verbose),
+                // Frames are not shown while several days are displayed at once
+                emit_frames: animate && jobs <= 1,
+                // The confirmed answers only apply to the default inputs
+                answers: match input {
+                    Some(_) => Default::default(),
+                    None => Arc::new(answers::default_answers()),
+                },
+                input_providers: match input {
+                    Some(input) => vec![input_provider(&input)],
+                    None => inputs::default_providers(),
+                },
+                jobs,
+                profile: profile.unwrap_or(inputs::DEFAULT_PROFILE.to_owned()),
+                ..Default::default()
+            };
+            let runner = RunnerOptions {
+                isolation: isolation.limits(),
+                record,
+            };
+            match format {
+                OutputFormat::Text if all_profiles => {
+                    solve_all_profiles(config, year, days, options, runner)
+                }
+                OutputFormat::Text => solve_days(config, year, days, options, runner, all_variants),
+                format => {
+                    set_ctrlc_handler(&options.cancellation_token);
+                    let reports = match all_profiles {
+                        true => solve::collect_all_profiles(days, &options, &runner),
+                        false => Ok(solve::collect_days(days, &options, &runner)),
+                    };
+                    match reports {
+                        Ok(reports) => report::print_reports(&reports, format),
+                        Err(err) => {
+                            eprintln!("Error: {}", err);
+                            process::exit(1);
+                        }
+                    }
                 }
             }
-
-            solve_days(config, year, days);
         }
-        Some(Command::Ui) => _ = aoc_ui::entry::main(),
+        Some(Command::Bench {
+            year,
+            days,
+            runs,
+            variant,
+            save_baseline,
+            compare,
+            threshold,
+        }) => {
+            let year = year.unwrap_or(timing::latest_aoc_date().year);
+            let days = match select_days(year, days) {
+                Some(days) => days,
+                None => return,
+            };
+            let options = SolveOptions {
+                variant,
+                log_level: LogLevel::Error,
+                ..Default::default()
+            };
+            bench(
+                days,
+                runs,
+                options,
+                save_baseline,
+                compare.then_some(threshold),
+            );
+        }
+        Some(Command::Watch { year, day, hot }) => {
+            let year = year.unwrap_or(timing::latest_aoc_date().year);
+            if let Err(err) = watch::watch_day(YearDay::new(year, day), hot) {
+                println!("Error: {}", err);
+                process::exit(1);
+            }
+        }
+        Some(Command::Serve {
+            stdio,
+            http,
+            web_dir,
+            time_limit,
+            verbose,
+            isolation,
+        }) => {
+            let options = SolveOptions {
+                part_time_limit: time_limit,
+                log_level: log_level_from_verbosity(verbose),
+                ..Default::default()
+            };
+            let isolation = isolation.limits();
+            let result = match http {
+                Some(port) => http::serve_http(port, web_dir, options, isolation),
+                None if stdio => serve::serve_stdio(options, isolation),
+                None => Ok(()),
+            };
+            if let Err(err) = result {
+                eprintln!("Error: {}", err);
+                process::exit(1);
+            }
+        }
+        Some(Command::Ui {
+            record,
+            replay,
+            speed,
+            hot_reload,
+        }) => {
+            let run_mode = match (record, replay) {
+                _ if hot_reload => RunMode::HotReload(watch::hot_library_path()),
+                (Some(path), _) => RunMode::Record(path),
+                (_, Some(path)) => match recording::load(&path) {
+                    Ok(recording) => RunMode::Replay(Arc::new(recording), speed),
+                    Err(err) => {
+                        println!(
+                            "Error: could not load recording '{}'! {}",
+                            path.display(),
+                            err
+                        );
+                        process::exit(1);
+                    }
+                },
+                _ => RunMode::Solve,
+            };
+            _ = aoc_ui::entry::main(run_mode);
+        }
         Some(Command::Day12Extra) => extras::day12_extra(),
+        Some(Command::Worker) => unreachable!("the worker is run before loading the configuration"),
         None => {
             if let Some(yd) = solutions::create_map().keys().sorted().rev().next() {
-                solve_days(config, yd.year, vec![yd.clone()]);
+                solve_days(
+                    config,
+                    yd.year,
+                    vec![yd.clone()],
+                    SolveOptions {
+                        log_level: log_level_from_verbosity(0),
+                        ..Default::default()
+                    },
+                    Default::default(),
+                    false,
+                );
             } else {
                 println!("Error: no solution found!");
                 return;
@@ -64,23 +237,230 @@ fn main() {
     }
 }
 
-fn solve_days(config: Config, year: i32, days: Vec<YearDay>) {
+fn input_provider(input: &str) -> Arc<dyn InputProvider> {
+    let path = PathBuf::from(input);
+    match input {
+        "-" => Arc::new(StdinInput::default()),
+        _ if path.is_dir() => Arc::new(DirectoryInputs { path }),
+        _ => Arc::new(FileInput { path }),
+    }
+}
+
+/// Returns the given days of the year, or every implemented day of the year if none is given.
+fn select_days(year: i32, days: Vec<u32>) -> Option<Vec<YearDay>> {
+    let days = match days.len() {
+        0 => solutions::create_map()
+            .into_keys()
+            .filter(|x| x.year == year)
+            .sorted()
+            .collect_vec(),
+        _ => days
+            .into_iter()
+            .map(|day| YearDay::new(year, day))
+            .unique()
+            .collect_vec(),
+    };
+    if days.is_empty() {
+        println!("Error: no solution found for {}!", year);
+        return None;
+    }
+
+    Some(days)
+}
+
+/// Cancels the running solution on the first Ctrl-C, exits on the second one.
+fn set_ctrlc_handler(token: &CancellationToken) {
+    let handler_token = token.clone();
+    ctrlc::set_handler(move || match handler_token.is_cancelled() {
+        true => process::exit(130),
+        false => handler_token.cancel(),
+    })
+    .expect("Ctrl-C handler should be set");
+}
+
+/// Only warnings and errors are logged by default.
+fn log_level_from_verbosity(verbose: u8) -> LogLevel {
+    match verbose {
+        0 => LogLevel::Warn,
+        1 => LogLevel::Info,
+        2 => LogLevel::Debug,
+        _ => LogLevel::Trace,
+    }
+}
+
+fn solve_days(
+    config: Config,
+    year: i32,
+    days: Vec<YearDay>,
+    options: SolveOptions,
+    runner: RunnerOptions,
+    all_variants: bool,
+) {
     let days_str = days
         .iter()
         .map(|x| x.day.to_string())
         .collect::<Vec<_>>()
         .join(", ");
     println!("Solving days for {}: {}", year, days_str);
-    let total_duration = days.iter().fold(Duration::default(), |a, yd| {
-        a + solve::run_solution(&config, yd.year, yd.day).unwrap()
-    });
 
-    if days.len() > 1 {
+    set_ctrlc_handler(&options.cancellation_token);
+
+    if all_variants {
+        for yd in days.iter() {
+            if let Err(err) = solve::run_all_variants(&config, yd.year, yd.day, &options, &runner) {
+                println!("Error solving day {}: {}", yd.day, err);
+            }
+            if options.cancellation_token.is_cancelled() {
+                println!("\nSolving cancelled.");
+                return;
+            }
+        }
+        return;
+    }
+
+    match solve::run_days(&config, days, &options, &runner) {
+        Some(summary) => print_summary(&summary, &options),
+        None => println!("\nSolving cancelled."),
+    }
+}
+
+/// Solves the days with the input of every profile, then lists the profiles with wrong answers.
+fn solve_all_profiles(
+    config: Config,
+    year: i32,
+    days: Vec<YearDay>,
+    options: SolveOptions,
+    runner: RunnerOptions,
+) {
+    println!(
+        "Solving days for {} with every profile: {}",
+        year,
+        days.iter().map(|x| x.day).join(", ")
+    );
+    set_ctrlc_handler(&options.cancellation_token);
+
+    let summaries = match solve::run_all_profiles(&config, days, &options, &runner) {
+        Ok(Some(summaries)) => summaries,
+        Ok(None) => return println!("\nSolving cancelled."),
+        Err(err) => return println!("Error: {}", err),
+    };
+    for (profile, summary) in &summaries {
+        if summary.days > 1 || !summary.failures.is_empty() {
+            println!("\n--- Profile {} summary ---", profile);
+            print_summary(summary, &options);
+        }
+    }
+
+    let wrong_profiles = summaries
+        .iter()
+        .filter(|(_, summary)| !summary.wrong_answers.is_empty())
+        .collect_vec();
+    if !wrong_profiles.is_empty() {
+        println!("\nProfiles with answers differing from their confirmed ones:");
+        for (profile, summary) in wrong_profiles {
+            println!(
+                "  {}: {}",
+                profile,
+                summary
+                    .wrong_answers
+                    .iter()
+                    .map(|(year_day, part)| format!("day {} part {}", year_day.day, part))
+                    .join(", ")
+            );
+        }
+    }
+}
+
+/// Prints the totals and the failures of a run.
+fn print_summary(summary: &SolveSummary, options: &SolveOptions) {
+    if summary.days > 1 {
         println!(
             "\n{} solutions run for {} in total.",
-            days.len(),
-            fmt_duration(&total_duration)
+            summary.days,
+            fmt_duration(&summary.duration)
         );
+        if options.jobs > 1 {
+            println!(
+                "Finished in {} using {} jobs.",
+                fmt_duration(&summary.elapsed),
+                options.jobs
+            );
+        }
+    }
+    if !summary.failures.is_empty() {
+        println!("Failures:");
+        for (year_day, part, reason) in &summary.failures {
+            match part {
+                Some(part) => println!("  Day {} part {}: {}", year_day.day, part, reason),
+                None => println!("  Day {}: {}", year_day.day, reason),
+            }
+        }
+    }
+}
+
+fn bench(
+    days: Vec<YearDay>,
+    runs: usize,
+    options: SolveOptions,
+    save_baseline: bool,
+    threshold: Option<f64>,
+) {
+    println!(
+        "Benchmarking days for {}: {}",
+        days[0].year,
+        days.iter().map(|x| x.day).join(", ")
+    );
+    set_ctrlc_handler(&options.cancellation_token);
+
+    let baseline = match threshold {
+        Some(_) => match BenchRun::load(Path::new(bench::DEFAULT_BASELINE_PATH)) {
+            Ok(baseline) => {
+                println!("Comparing with the baseline of {}", baseline.timestamp);
+                Some(baseline)
+            }
+            Err(err) => {
+                println!("Error: could not load baseline! {}", err);
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let (run, failed_days) = bench::run_bench(&days, runs.max(1), &options, baseline.as_ref());
+    if options.cancellation_token.is_cancelled() {
+        println!("\nBenchmark cancelled.");
+        return;
+    }
+    if let Err(err) = run.append_to_history(Path::new(bench::DEFAULT_HISTORY_PATH)) {
+        println!("Warning: could not save history! {}", err);
+    }
+
+    let regressions = match (&baseline, threshold) {
+        (Some(baseline), Some(threshold)) => bench::regressions(&run, baseline, threshold),
+        _ => Vec::new(),
+    };
+    if save_baseline {
+        match run.save(Path::new(bench::DEFAULT_BASELINE_PATH)) {
+            Ok(_) => println!("\nBaseline saved to {}", bench::DEFAULT_BASELINE_PATH),
+            Err(err) => println!("\nWarning: could not save baseline! {}", err),
+        }
+    }
+
+    if !regressions.is_empty() {
+        println!(
+            "\nRegression over {:.1}% for days: {}",
+            threshold.unwrap_or_default(),
+            regressions.iter().map(|x| x.day).join(", ")
+        );
+    }
+    if !failed_days.is_empty() {
+        println!(
+            "\nFailed days: {}",
+            failed_days.iter().map(|x| x.day).join(", ")
+        );
+    }
+    if !regressions.is_empty() || !failed_days.is_empty() {
+        process::exit(1);
     }
 }
 
@@ -119,7 +499,9 @@ mod extras {
 
     pub fn day12_extra() {
         let ctx = Context {
-            raw_input: inputs::get(&YearDay::new(2022, 12)).unwrap().to_owned(),
+            raw_input: inputs::get(&YearDay::new(2022, 12), inputs::DEFAULT_PROFILE)
+                .unwrap()
+                .to_owned(),
             ..Default::default()
         };
 
@@ -1,5 +1,15 @@
 use regex::Regex;
-use std::{borrow::Cow, error::Error, fs, io::Write, path::PathBuf};
+use std::{
+    borrow::Cow,
+    collections::hash_map::DefaultHasher,
+    env,
+    error::Error,
+    fs::{self, DirEntry},
+    hash::Hasher,
+    io::Write,
+    path::PathBuf,
+    process::Command,
+};
 
 type GenericResult<T> = Result<T, Box<dyn Error>>;
 
@@ -12,13 +22,47 @@ const SOLUTION_DIRECTORY: &'static str = "src/solutions/";
 const SOLUTION_MODULE_TEMPLATE_PATH: &'static str = "templates/solution/mod.rs.template";
 const TEST_DIRECTORY: &'static str = "src/tests/";
 const TEST_MODULE_TEMPLATE_PATH: &'static str = "templates/test/mod.rs.template";
+/// Sources defining the types passed to a hot reloaded library.
+const INTERFACE_PATHS: [&str; 3] = ["src/core/", "src/answers.rs", "src/inputs.rs"];
 
 fn main() {
     println!("cargo:rerun-if-changed={}", SOLUTION_DIRECTORY);
     println!("cargo:rerun-if-changed={}", TEST_DIRECTORY);
+    for path in INTERFACE_PATHS {
+        println!("cargo:rerun-if-changed={}", path);
+    }
     if let Err(e) = generate_modules() {
         eprintln!("Error: {}", e);
     }
+    let hash = interface_hash().unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        0
+    });
+    println!("cargo:rustc-env=AOC_INTERFACE_HASH={:016x}", hash);
+}
+
+/// Hashes the compiler version and the interface sources, so that a hot reloaded library
+/// is only used by a program built from the same interface with the same compiler.
+fn interface_hash() -> GenericResult<u64> {
+    let mut hasher = DefaultHasher::new();
+    let rustc = env::var("RUSTC").unwrap_or("rustc".to_owned());
+    hasher.write(&Command::new(rustc).arg("--version").output()?.stdout);
+    for path in INTERFACE_PATHS {
+        let mut files = match fs::metadata(path)?.is_dir() {
+            true => fs::read_dir(path)?
+                .filter_map(|x| x.ok())
+                .map(|x| x.path())
+                .filter(|x| x.is_file())
+                .collect::<Vec<_>>(),
+            false => vec![PathBuf::from(path)],
+        };
+        files.sort();
+        for file in files {
+            hasher.write(&fs::read(file)?);
+        }
+    }
+
+    Ok(hasher.finish())
 }
 
 fn generate_modules() -> GenericResult<()> {
@@ -45,8 +89,8 @@ fn generate_source_module(solution_dir: &str) -> GenericResult<()> {
         }
     }
 
-    let directories = fs::read_dir(solution_dir)?
-        .filter_map(|x| x.ok())
+    let directories = sorted_entries(solution_dir)?
+        .into_iter()
         .filter(|x| x.path().is_dir());
     for entry in directories {
         let path_str = entry.path().to_str().unwrap().to_owned();
@@ -91,8 +135,8 @@ fn generate_test_module(test_dir: &str) -> GenericResult<()> {
         .map(|(_, mod_name)| format!("pub mod {};", mod_name))
         .collect::<Vec<_>>();
 
-    let directories = fs::read_dir(test_dir)?
-        .filter_map(|x| x.ok())
+    let directories = sorted_entries(test_dir)?
+        .into_iter()
         .filter(|x| x.path().is_dir());
     for entry in directories {
         let path_str = entry.path().to_str().unwrap().to_owned();
@@ -102,8 +146,8 @@ fn generate_test_module(test_dir: &str) -> GenericResult<()> {
     }
 
     let file_prefix_regex = Regex::new(r"(.*)\..*").unwrap();
-    let files = fs::read_dir(test_dir)?
-        .filter_map(|x| x.ok())
+    let files = sorted_entries(test_dir)?
+        .into_iter()
         .filter(|x| x.path().is_file() && x.path().file_name().unwrap() != "mod.rs");
     for entry in files {
         let file_name = entry.file_name().to_str().unwrap().to_owned();
@@ -149,13 +193,20 @@ fn get_module_file_name(module_dir: &str) -> PathBuf {
     path
 }
 
+/// Returns the entries of the directory sorted by name, so that the generated modules do not depend on the file system.
+fn sorted_entries(directory: &str) -> GenericResult<Vec<DirEntry>> {
+    let mut entries = fs::read_dir(directory)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|x| x.file_name());
+
+    Ok(entries)
+}
+
 fn collect_modules_from_dir(
     directory: &str,
     solution_module_re: &Regex,
 ) -> GenericResult<Vec<(String, String)>> {
     let mut modules = Vec::new();
-    for entry in fs::read_dir(directory)? {
-        let entry = entry?;
+    for entry in sorted_entries(directory)? {
         if entry.path().is_dir() {
             continue;
         }
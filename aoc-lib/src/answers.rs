@@ -0,0 +1,111 @@
+use crate::core::file_util::INPUT_PATH;
+use crate::helpers::is_wasm;
+use crate::inputs::{DEFAULT_PROFILE, INPUT_DIR};
+use crate::solution::Answer;
+use crate::util::{day_str, GenericResult, YearDay};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+pub const ANSWERS_FILE_NAME: &str = "answers.json";
+
+/// Result of comparing the answer of a part with its confirmed answer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    /// The answer differs from the confirmed answer contained.
+    Wrong(String),
+    /// There is no confirmed answer for the part.
+    Unknown,
+}
+
+/// Confirmed answers for the inputs of each day, stored as
+/// `{ "2022/05": { "1": "CMZ", "2": "MCD" }, "2022/05/alice": { "1": "QNHWJVJZW" } }`,
+/// where the key without a profile belongs to the default profile.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(transparent)]
+pub struct KnownAnswers {
+    days: BTreeMap<String, BTreeMap<u8, String>>,
+}
+impl KnownAnswers {
+    /// Reads the answers from the given file, or returns no answers if the file does not exist.
+    pub fn load(path: &Path) -> GenericResult<KnownAnswers> {
+        match fs::read_to_string(path) {
+            Ok(json) => Ok(serde_json::from_str(&json)
+                .map_err(|err| format!("invalid answers file '{}': {}", path.display(), err))?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Default::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Answers embedded from `aoc-lib/input` at compile time.
+    pub fn embedded() -> KnownAnswers {
+        INPUT_DIR
+            .get_file(ANSWERS_FILE_NAME)
+            .and_then(|file| file.contents_utf8())
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, year_day: &YearDay, profile: &str, part: u8) -> Option<&str> {
+        self.days
+            .get(&key(year_day, profile))
+            .and_then(|parts| parts.get(&part))
+            .map(|answer| answer.as_str())
+    }
+
+    /// Returns the confirmed answers of the parts of a day.
+    pub fn parts(&self, year_day: &YearDay, profile: &str) -> BTreeMap<u8, String> {
+        self.days
+            .get(&key(year_day, profile))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn insert(&mut self, year_day: &YearDay, profile: &str, part: u8, answer: String) {
+        self.days
+            .entry(key(year_day, profile))
+            .or_default()
+            .insert(part, answer);
+    }
+
+    /// Compares the answer with the confirmed answer of the part.
+    pub fn check(&self, year_day: &YearDay, profile: &str, part: u8, answer: &Answer) -> Verdict {
+        check(self.get(year_day, profile, part), answer)
+    }
+}
+
+fn key(year_day: &YearDay, profile: &str) -> String {
+    match profile {
+        DEFAULT_PROFILE => format!("{}/{}", year_day.year, day_str(year_day.day)),
+        profile => format!("{}/{}/{}", year_day.year, day_str(year_day.day), profile),
+    }
+}
+
+/// Compares the answer with the confirmed answer, if there is one.
+pub fn check(known: Option<&str>, answer: &Answer) -> Verdict {
+    match known {
+        Some(known) if known.trim() == answer.to_string().trim() => Verdict::Correct,
+        Some(known) => Verdict::Wrong(known.to_owned()),
+        None => Verdict::Unknown,
+    }
+}
+
+/// Path of the answers file next to the inputs.
+pub fn answers_path() -> PathBuf {
+    PathBuf::from(INPUT_PATH).join(ANSWERS_FILE_NAME)
+}
+
+/// Reads the answers file at runtime, so new answers can be used without rebuilding,
+/// falling back to the embedded answers. Only the embedded answers are available in WASM.
+pub fn default_answers() -> KnownAnswers {
+    let path = answers_path();
+    match is_wasm() || !path.exists() {
+        true => KnownAnswers::embedded(),
+        false => KnownAnswers::load(&path).unwrap_or_else(|err| {
+            eprintln!("Warning: {}", err);
+            Default::default()
+        }),
+    }
+}
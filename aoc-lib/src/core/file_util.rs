@@ -1,21 +1,28 @@
-use crate::{solution::SolutionInfo, util};
+use crate::{inputs::DEFAULT_PROFILE, solution::SolutionInfo, util};
 use std::path::PathBuf;
 
-const INPUT_PATH: &'static str = "aoc-lib/input";
+pub const INPUT_PATH: &'static str = "aoc-lib/input";
 
 pub fn year_directory_name(year: i32) -> String {
     format!("year{}", year)
 }
 
-pub fn input_file_name(info: &SolutionInfo) -> String {
-    format!("day{}.txt", util::day_str(info.day))
+/// Path of the input file of the profile within the year directory.
+pub fn input_file_name(day: u32, profile: &str) -> PathBuf {
+    match profile {
+        DEFAULT_PROFILE => PathBuf::from(format!("day{}.txt", util::day_str(day))),
+        profile => PathBuf::from_iter([
+            format!("day{}", util::day_str(day)),
+            format!("{}.txt", profile),
+        ]),
+    }
 }
 
-pub fn input_file_path(info: &SolutionInfo) -> String {
+pub fn input_file_path(info: &SolutionInfo, profile: &str) -> String {
     PathBuf::from_iter([
-        INPUT_PATH,
-        &year_directory_name(info.year),
-        &input_file_name(info),
+        PathBuf::from(INPUT_PATH),
+        PathBuf::from(year_directory_name(info.year)),
+        input_file_name(info.day, profile),
     ])
     .to_str()
     .unwrap()
@@ -0,0 +1,184 @@
+use crate::core::solution_runner::{
+    run_many_with, run_solution, send_and_close, Input, SolutionRunner, SolveOptions,
+    SolveProgress, SyncStream,
+};
+use crate::solution::SolutionType;
+use crate::solutions;
+use crate::util::{GenericResult, YearDay};
+use libloading::Library;
+use std::env;
+use std::ffi::{c_char, CStr};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::SystemTime;
+
+/// Hash of the compiler version and of the sources defining the types passed to the library, see build.rs.
+const INTERFACE_HASH: &str = env!("AOC_INTERFACE_HASH");
+
+type InterfaceHashFn = extern "C" fn() -> *const c_char;
+type CreateListFn = fn() -> Vec<SolutionType>;
+type RunSolutionFn = fn(YearDay, Input, Arc<Mutex<dyn SyncStream>>, SolveOptions);
+
+/// Libraries loaded so far by their path and modification time.
+/// They are never unloaded, as the threads which ran their solutions may still hold their thread locals.
+static LIBRARIES: Mutex<Vec<(PathBuf, SystemTime, &'static HotLibrary)>> = Mutex::new(Vec::new());
+
+/// Returns the [`INTERFACE_HASH`] of the library as a null-terminated string.
+#[no_mangle]
+pub extern "C" fn aoc_interface_hash() -> *const c_char {
+    concat!(env!("AOC_INTERFACE_HASH"), "\0").as_ptr() as *const c_char
+}
+
+#[no_mangle]
+pub fn aoc_create_list() -> Vec<SolutionType> {
+    solutions::create_list()
+}
+
+#[no_mangle]
+pub fn aoc_run_solution(
+    year_day: YearDay,
+    input: Input,
+    tx: Arc<Mutex<dyn SyncStream>>,
+    options: SolveOptions,
+) {
+    run_solution(year_day, input, tx, options)
+}
+
+/// Solutions of aoc-lib built as a dynamic library, e.g. with
+/// `cargo rustc -p aoc-lib --lib --crate-type dylib`.
+///
+/// The days are solved entirely by the library, as it has its own copy of the standard library,
+/// so the panics of its solutions cannot be caught by the current program.
+pub struct HotLibrary {
+    create_list: CreateListFn,
+    run_solution: RunSolutionFn,
+    _library: Library,
+}
+impl HotLibrary {
+    /// Loads a copy of the library, as the file is replaced by the next build, and loading
+    /// the same path again may return the library loaded before.
+    fn open(path: &Path, generation: usize) -> GenericResult<HotLibrary> {
+        let file_name = path.file_name().ok_or("invalid library path")?;
+        let copy = env::temp_dir().join(format!(
+            "aoc-hot-{}-{}-{}",
+            process::id(),
+            generation,
+            file_name.to_string_lossy()
+        ));
+        fs::copy(path, &copy)?;
+        // SAFETY: the initializers of aoc-lib have no requirements
+        let library = unsafe { Library::new(&copy) };
+        // The copy is not needed once loaded, except on Windows where it cannot be removed yet
+        _ = fs::remove_file(&copy);
+        let library = library?;
+
+        // SAFETY: the signatures match the exported functions, and the Rust functions are only
+        // resolved if the library was built from the same interface with the same compiler
+        unsafe {
+            let interface_hash = library.get::<InterfaceHashFn>(b"aoc_interface_hash")?;
+            check_interface_hash(CStr::from_ptr(interface_hash()))?;
+            Ok(HotLibrary {
+                create_list: *library.get::<CreateListFn>(b"aoc_create_list")?,
+                run_solution: *library.get::<RunSolutionFn>(b"aoc_run_solution")?,
+                _library: library,
+            })
+        }
+    }
+
+    /// Returns the solutions of the library.
+    pub fn create_list(&self) -> Vec<SolutionType> {
+        (self.create_list)()
+    }
+
+    /// Solves the day with the library, see [`run_solution`].
+    pub fn run_solution(
+        &self,
+        year_day: YearDay,
+        input: Input,
+        tx: Arc<Mutex<dyn SyncStream>>,
+        options: SolveOptions,
+    ) {
+        (self.run_solution)(year_day, input, tx, options)
+    }
+}
+
+/// Refuses a library whose interface hash differs from the one of the current program,
+/// as calling its Rust functions would be undefined behavior.
+pub fn check_interface_hash(library_hash: &CStr) -> GenericResult {
+    match library_hash.to_str() {
+        Ok(hash) if hash == INTERFACE_HASH => Ok(()),
+        _ => Err("the library was built from different sources or with a different compiler, rebuild both")?,
+    }
+}
+
+/// Returns the library at the given path, loading it again if the file changed since it was last loaded.
+pub fn load(path: &Path) -> GenericResult<&'static HotLibrary> {
+    let modified = fs::metadata(path)?.modified()?;
+    let mut libraries = LIBRARIES.lock().unwrap();
+    let loaded = libraries
+        .iter()
+        .rfind(|(p, m, _)| p == path && *m == modified);
+    if let Some((_, _, library)) = loaded {
+        return Ok(library);
+    }
+
+    let library = Box::leak(Box::new(HotLibrary::open(path, libraries.len())?));
+    libraries.push((path.to_owned(), modified, library));
+
+    Ok(library)
+}
+
+/// Runs the solutions of aoc-lib built as a dynamic library on threads of the current process.
+/// The library is reloaded at the start of a run if it was rebuilt since the last one,
+/// so a long running program picks up the changed solutions without restarting.
+pub struct HotReloadSolutionRunner {
+    pub options: SolveOptions,
+    pub path: PathBuf,
+}
+impl HotReloadSolutionRunner {
+    fn load(&self) -> Result<&'static HotLibrary, SolveProgress> {
+        load(&self.path).map_err(|err| {
+            SolveProgress::Error(format!("Unable to load '{}': {}", self.path.display(), err))
+        })
+    }
+}
+impl<T: SyncStream + Default + 'static> SolutionRunner<T> for HotReloadSolutionRunner {
+    fn run(&self, year_day: YearDay, input: Input) -> Arc<Mutex<T>> {
+        let stream = Arc::new(Mutex::new(T::default()));
+        let stream_copy = Arc::clone(&stream);
+        let options = self.options.clone();
+        let library = self.load();
+        thread::spawn(move || match library {
+            Ok(library) => library.run_solution(year_day, input, stream_copy, options),
+            Err(err) => send_and_close(&stream_copy, year_day, SystemTime::now(), err),
+        });
+
+        stream
+    }
+
+    fn run_many(&self, days: Vec<YearDay>) -> Arc<Mutex<T>> {
+        let stream = Arc::new(Mutex::new(T::default()));
+        let stream_copy = Arc::clone(&stream);
+        let options = self.options.clone();
+        let library = self.load();
+        thread::spawn(move || match library {
+            Ok(library) => run_many_with(&days, stream_copy, options, |year_day, tx, options| {
+                library.run_solution(year_day, Input::Default, tx, options)
+            }),
+            Err(err) => {
+                let mut tx = stream_copy.lock().unwrap();
+                tx.send(err);
+                tx.close();
+            }
+        });
+
+        stream
+    }
+
+    fn cancel(&self) {
+        self.options.cancellation_token.cancel();
+    }
+}
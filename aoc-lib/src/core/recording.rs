@@ -0,0 +1,225 @@
+use crate::core::solution_runner::{
+    ChannelSyncStream, Input, ResultPack, SolutionRunner, SolveProgress, SyncStream,
+};
+use crate::solution::CancellationToken;
+use crate::util::{GenericResult, YearDay};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use wasm_timer::Instant;
+
+/// How often the cancellation is checked while waiting for the next recorded event.
+const REPLAY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Event of a recorded run, with the time it was sent relative to the start of the run.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordedProgress {
+    pub elapsed: Duration,
+    pub progress: SolveProgress,
+}
+
+/// Stream forwarding its events to another stream while writing them to a recording, one JSON per line.
+pub struct TeeSyncStream<T: SyncStream + ?Sized> {
+    inner: Arc<Mutex<T>>,
+    writer: Option<BufWriter<File>>,
+    start: Instant,
+}
+impl<T: SyncStream + ?Sized> TeeSyncStream<T> {
+    /// Creates the recording at the given path, replacing an existing one.
+    pub fn create(inner: Arc<Mutex<T>>, path: &Path) -> GenericResult<TeeSyncStream<T>> {
+        Ok(TeeSyncStream {
+            inner,
+            writer: Some(BufWriter::new(File::create(path)?)),
+            start: Instant::now(),
+        })
+    }
+}
+impl<T: SyncStream + ?Sized> SyncStream for TeeSyncStream<T> {
+    fn send(&mut self, item: SolveProgress) {
+        if let Some(writer) = &mut self.writer {
+            let recorded = RecordedProgress {
+                elapsed: self.start.elapsed(),
+                progress: item,
+            };
+            let json = serde_json::to_string(&recorded).expect("progress should be serializable");
+            // Stop recording instead of failing the run
+            if writeln!(writer, "{}", json).is_err() {
+                self.writer = None;
+            }
+            self.inner.lock().unwrap().send(recorded.progress);
+        } else {
+            self.inner.lock().unwrap().send(item);
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(mut writer) = self.writer.take() {
+            _ = writer.flush();
+        }
+        self.inner.lock().unwrap().close();
+    }
+
+    fn next_items(&mut self) -> Option<Vec<SolveProgress>> {
+        self.inner.lock().unwrap().next_items()
+    }
+}
+
+/// Reads a recording written by a [`TeeSyncStream`].
+pub fn load(path: &Path) -> GenericResult<Vec<RecordedProgress>> {
+    let mut recording = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            recording.push(serde_json::from_str(&line)?);
+        }
+    }
+
+    Ok(recording)
+}
+
+/// Runs the solutions with another runner, recording the events of each run to the given path.
+/// Each run replaces the recording of the previous one.
+pub struct RecordingSolutionRunner {
+    pub runner: Box<dyn SolutionRunner<ChannelSyncStream>>,
+    pub path: PathBuf,
+}
+impl RecordingSolutionRunner {
+    /// Forwards the events of the source stream to a new stream on a separate thread, recording them on the way.
+    fn tee<T: SyncStream + Default + 'static>(
+        &self,
+        source: Arc<Mutex<ChannelSyncStream>>,
+    ) -> Arc<Mutex<T>> {
+        let mut rx = source
+            .lock()
+            .unwrap()
+            .take_receiver()
+            .expect("receiver of a new stream should be available");
+        let stream = Arc::new(Mutex::new(T::default()));
+        let stream_copy = Arc::clone(&stream);
+        let path = self.path.clone();
+        thread::spawn(move || {
+            let mut tx = match TeeSyncStream::create(Arc::clone(&stream_copy), &path) {
+                Ok(tx) => tx,
+                Err(err) => {
+                    stream_copy
+                        .lock()
+                        .unwrap()
+                        .send(SolveProgress::Error(format!(
+                            "Unable to record to '{}': {}",
+                            path.display(),
+                            err
+                        )));
+                    TeeSyncStream {
+                        inner: stream_copy,
+                        writer: None,
+                        start: Instant::now(),
+                    }
+                }
+            };
+            while let Some(item) = rx.recv() {
+                tx.send(item);
+            }
+            tx.close();
+        });
+
+        stream
+    }
+}
+impl<T: SyncStream + Default + 'static> SolutionRunner<T> for RecordingSolutionRunner {
+    fn run(&self, day: YearDay, input: Input) -> Arc<Mutex<T>> {
+        self.tee(self.runner.run(day, input))
+    }
+
+    fn run_many(&self, days: Vec<YearDay>) -> Arc<Mutex<T>> {
+        self.tee(self.runner.run_many(days))
+    }
+
+    fn cancel(&self) {
+        self.runner.cancel();
+    }
+}
+
+/// Plays back a recorded run instead of solving anything, keeping the original timing scaled by the speed.
+/// Every run plays the whole recording regardless of the requested days.
+pub struct ReplaySolutionRunner {
+    pub recording: Arc<Vec<RecordedProgress>>,
+    /// Playback speed relative to the original run, e.g. 2.0 plays twice as fast.
+    pub speed: f32,
+    pub cancellation_token: CancellationToken,
+}
+impl ReplaySolutionRunner {
+    pub fn new(recording: Arc<Vec<RecordedProgress>>, speed: f32) -> ReplaySolutionRunner {
+        ReplaySolutionRunner {
+            recording,
+            speed,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    fn replay<T: SyncStream + Default + 'static>(&self) -> Arc<Mutex<T>> {
+        let stream = Arc::new(Mutex::new(T::default()));
+        let stream_copy = Arc::clone(&stream);
+        let recording = Arc::clone(&self.recording);
+        let speed = self.speed;
+        let cancellation_token = self.cancellation_token.clone();
+        thread::spawn(move || replay(&recording, speed, stream_copy, &cancellation_token));
+
+        stream
+    }
+}
+impl<T: SyncStream + Default + 'static> SolutionRunner<T> for ReplaySolutionRunner {
+    fn run(&self, _day: YearDay, _input: Input) -> Arc<Mutex<T>> {
+        self.replay()
+    }
+
+    fn run_many(&self, _days: Vec<YearDay>) -> Arc<Mutex<T>> {
+        self.replay()
+    }
+
+    fn cancel(&self) {
+        self.cancellation_token.cancel();
+    }
+}
+
+/// Sends the recorded events to the stream at their scaled time.
+/// Cancellation stops the playback with a [`SolveProgress::Cancelled`] event for the day being replayed.
+fn replay<T: SyncStream>(
+    recording: &[RecordedProgress],
+    speed: f32,
+    tx: Arc<Mutex<T>>,
+    cancellation_token: &CancellationToken,
+) {
+    let start = Instant::now();
+    let mut year_day = YearDay::default();
+    for recorded in recording {
+        let due = start + recorded.elapsed.div_f32(speed.max(0.001));
+        loop {
+            if cancellation_token.is_cancelled() {
+                let mut tx = tx.lock().unwrap();
+                tx.send(SolveProgress::Cancelled(ResultPack {
+                    year_day,
+                    part: None,
+                    value: (),
+                    duration: start.elapsed(),
+                    verdict: None,
+                }));
+                return tx.close();
+            }
+            let now = Instant::now();
+            if now >= due {
+                break;
+            }
+            thread::sleep((due - now).min(REPLAY_POLL_INTERVAL));
+        }
+
+        if let SolveProgress::Start(day, _) = &recorded.progress {
+            year_day = *day;
+        }
+        tx.lock().unwrap().send(recorded.progress.clone());
+    }
+    tx.lock().unwrap().close();
+}
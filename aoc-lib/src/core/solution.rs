@@ -1,8 +1,70 @@
 use crate::util::{GenericResult, YearDay};
 use regex::Regex;
-use std::{cell::RefCell, error::Error, fmt, str::FromStr};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fmt, panic,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use wasm_timer::Instant;
 
-pub type SolutionResult = GenericResult<String>;
+pub type SolutionResult = GenericResult<Answer>;
+
+/// Typed value of a solved part.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    /// Multi-line ascii art, with the text recognized from it if available.
+    Grid {
+        lines: Vec<String>,
+        text: Option<String>,
+    },
+}
+impl Answer {
+    pub fn grid(lines: Vec<String>, text: Option<String>) -> Self {
+        Answer::Grid { lines, text }
+    }
+}
+/// Formats the primary value of the answer, which is the one to submit.
+/// Grids without a recognized text are formatted as their lines.
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+            Answer::Grid {
+                text: Some(text), ..
+            } => write!(f, "{}", text),
+            Answer::Grid { lines, .. } => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_owned())
+    }
+}
+/// Integers that do not fit in an `i64` become text answers instead of wrapping around.
+macro_rules! impl_answer_from_integer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                i64::try_from(value).map_or_else(|_| Answer::Text(value.to_string()), Answer::Integer)
+            }
+        })*
+    };
+}
+impl_answer_from_integer!(i32, i64, u32, u64, usize);
 
 /// Indicates that the solution method is not implemented yet.
 #[derive(Debug, Clone)]
@@ -14,11 +76,16 @@ impl fmt::Display for NotImplementedError {
     }
 }
 
+/// Variant label of solutions that do not specify one.
+pub const DEFAULT_VARIANT: &str = "default";
+
 pub type Title = SolutionInfo;
 pub struct SolutionInfo {
     pub year: i32,
     pub day: u32,
     pub title: String,
+    /// Distinguishes alternative solutions of the same day, e.g. "naive" and "optimized".
+    pub variant: String,
 }
 impl SolutionInfo {
     pub fn new(year: i32, day: u32, title: &'static str) -> Self {
@@ -26,9 +93,15 @@ impl SolutionInfo {
             year,
             day,
             title: title.to_owned(),
+            variant: DEFAULT_VARIANT.to_owned(),
         }
     }
 
+    pub fn with_variant(mut self, variant: &'static str) -> Self {
+        self.variant = variant.to_owned();
+        self
+    }
+
     pub fn year_day(&self) -> YearDay {
         YearDay {
             year: self.year,
@@ -37,21 +110,99 @@ impl SolutionInfo {
     }
 }
 
-pub trait ProgressHandler {
+pub trait ProgressHandler: Send {
     fn on_progress(&mut self, _value: f32) {}
+    fn on_log(&mut self, _level: LogLevel, _message: &str) {}
+    /// True, if the handler is ready to receive the next frame.
+    fn accepts_frame(&self) -> bool {
+        false
+    }
+    fn on_frame(&mut self, _lines: Vec<String>) {}
 }
 pub struct NopOnProgress;
 impl ProgressHandler for NopOnProgress {}
 
+/// Severity of a log message, ordered from the most to the least important.
+#[derive(
+    Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Shared flag to request the cooperative cancellation of a running solution.
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Unwind payload used to abort a solution after its cancellation token was set.
+#[derive(Debug, Clone)]
+pub struct Cancelled;
+impl Error for Cancelled {}
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "solution was cancelled")
+    }
+}
+
+/// Unwind payload used to abort a solution after its deadline has passed.
+#[derive(Debug, Clone)]
+pub struct TimedOut;
+impl Error for TimedOut {}
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "solution ran out of time")
+    }
+}
+
 pub struct Context {
     pub raw_input: String,
-    pub progress_handler: RefCell<Box<dyn ProgressHandler>>,
+    pub progress_handler: Mutex<Box<dyn ProgressHandler>>,
+    pub cancellation_token: CancellationToken,
+    pub deadline: Mutex<Option<Instant>>,
+    /// Least important level of the log messages which are passed to the progress handler.
+    pub log_level: LogLevel,
+    /// Pass the frames of the solution to the progress handler.
+    pub emit_frames: bool,
 }
 impl Default for Context {
     fn default() -> Self {
         Self {
             raw_input: Default::default(),
-            progress_handler: RefCell::new(Box::new(NopOnProgress)),
+            progress_handler: Mutex::new(Box::new(NopOnProgress)),
+            cancellation_token: Default::default(),
+            deadline: Default::default(),
+            log_level: Default::default(),
+            emit_frames: false,
         }
     }
 }
@@ -80,13 +231,101 @@ impl Context {
     }
 
     /// Updates the current progress percentage.
+    /// Aborts the solution if it has been interrupted, see [`Context::check_interrupted`].
     /// value range: 0..1
     pub fn progress(&self, value: f32) {
+        self.check_interrupted();
         let value = value.min(1.0).max(0.0);
-        self.progress_handler.borrow_mut().on_progress(value);
+        self.progress_handler.lock().unwrap().on_progress(value);
+    }
+
+    /// Sends a log message to the progress handler instead of printing it,
+    /// so it can be displayed by both the CLI and the UI.
+    pub fn log(&self, level: LogLevel, message: impl AsRef<str>) {
+        if self.log_enabled(level) {
+            let mut handler = self.progress_handler.lock().unwrap();
+            handler.on_log(level, message.as_ref());
+        }
+    }
+
+    /// Returns true if messages of the given level are logged.
+    /// Useful to skip building expensive log messages.
+    pub fn log_enabled(&self, level: LogLevel) -> bool {
+        level <= self.log_level
+    }
+
+    /// Sends an intermediate 2D state of the solution to be visualized.
+    /// The frame is rendered only if frames are enabled and the progress handler accepts it,
+    /// so calling this often is cheap. Aborts the solution if it has been interrupted.
+    pub fn frame(&self, render: impl FnOnce() -> Vec<String>) {
+        self.check_interrupted();
+        if self.emit_frames {
+            let mut handler = self.progress_handler.lock().unwrap();
+            if handler.accepts_frame() {
+                handler.on_frame(render());
+            }
+        }
+    }
+
+    /// Sends the last state of the solution regardless of the throttling of [`Context::frame`],
+    /// so the visualization ends on it. Rendered only if frames are enabled.
+    pub fn final_frame(&self, render: impl FnOnce() -> Vec<String>) {
+        if self.emit_frames {
+            self.progress_handler.lock().unwrap().on_frame(render());
+        }
+    }
+
+    /// Creates a handle to report progress in `total` steps, see [`ProgressScope`].
+    pub fn progress_scope(&self, total: usize) -> ProgressScope<'_> {
+        ProgressScope {
+            ctx: self,
+            done: Arc::new(AtomicU64::new(0.0f64.to_bits())),
+            step: 1.0 / total.max(1) as f64,
+        }
+    }
+
+    /// Unwinds with a [`Cancelled`] payload if the cancellation token has been set,
+    /// or with a [`TimedOut`] payload if the deadline has passed.
+    /// The solution runner catches the unwind and reports the interruption.
+    pub fn check_interrupted(&self) {
+        if self.cancellation_token.is_cancelled() {
+            panic::resume_unwind(Box::new(Cancelled));
+        }
+        if matches!(*self.deadline.lock().unwrap(), Some(deadline) if Instant::now() >= deadline) {
+            panic::resume_unwind(Box::new(TimedOut));
+        }
+    }
+}
+
+/// Cloneable handle to report progress from parallel code, created by [`Context::progress_scope`].
+/// Each tick completes one step of the scope. A sub-scope stands for a single step
+/// of its parent, so it should be completed instead of ticking the parent for that step.
+#[derive(Clone)]
+pub struct ProgressScope<'a> {
+    ctx: &'a Context,
+    /// Completed part of the root scope as the bits of an f64, so that small steps of deeply nested scopes are not lost.
+    done: Arc<AtomicU64>,
+    step: f64,
+}
+impl<'a> ProgressScope<'a> {
+    pub fn tick(&self) {
+        let add = |bits| Some((f64::from_bits(bits) + self.step).to_bits());
+        let previous = self
+            .done
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, add);
+        let done = f64::from_bits(previous.unwrap()) + self.step;
+        self.ctx.progress(done as f32);
+    }
+
+    /// Splits the next step of this scope into `total` smaller steps.
+    pub fn sub_scope(&self, total: usize) -> ProgressScope<'a> {
+        ProgressScope {
+            ctx: self.ctx,
+            done: Arc::clone(&self.done),
+            step: self.step / total.max(1) as f64,
+        }
     }
 }
-unsafe impl Sync for Context {}
 
 pub struct SolutionType {
     pub info: SolutionInfo,
@@ -126,3 +365,59 @@ pub trait Solution {
         Ok(())
     }
 }
+
+/// Alternative to [`Solution`] for days which parse their input once and share it between the parts.
+/// It is run as a [`Solution`] by wrapping it into [`ParseOnce`], parsing during initialization.
+pub trait ParsedSolution {
+    type Parsed;
+
+    fn info(&self) -> SolutionInfo;
+    fn parse(&mut self, ctx: &Context) -> GenericResult<Self::Parsed>;
+    fn part1(&mut self, input: &Self::Parsed, ctx: &Context) -> SolutionResult;
+    fn part2(&mut self, input: &Self::Parsed, ctx: &Context) -> SolutionResult;
+}
+
+/// Adapter running a [`ParsedSolution`] as a [`Solution`].
+pub struct ParseOnce<T: ParsedSolution> {
+    solution: T,
+    parsed: Option<T::Parsed>,
+}
+impl<T: ParsedSolution + Default> Default for ParseOnce<T> {
+    fn default() -> Self {
+        Self {
+            solution: Default::default(),
+            parsed: None,
+        }
+    }
+}
+impl<T: ParsedSolution> Solution for ParseOnce<T> {
+    fn info(&self) -> SolutionInfo {
+        self.solution.info()
+    }
+
+    fn init(&mut self, ctx: &Context) -> GenericResult {
+        self.parsed = Some(self.solution.parse(ctx)?);
+        Ok(())
+    }
+
+    fn part1(&mut self, ctx: &Context) -> SolutionResult {
+        let input = self.parsed.as_ref().ok_or("input is not parsed yet")?;
+        self.solution.part1(input, ctx)
+    }
+
+    fn part2(&mut self, ctx: &Context) -> SolutionResult {
+        let input = self.parsed.as_ref().ok_or("input is not parsed yet")?;
+        self.solution.part2(input, ctx)
+    }
+}
+
+/// Registers a [`ParsedSolution`] the same way as [`SolutionStatic`] does for a [`Solution`].
+pub trait ParsedSolutionStatic
+where
+    Self: ParsedSolution + Default + 'static,
+{
+    fn as_type() -> SolutionType {
+        ParseOnce::<Self>::as_type()
+    }
+}
+impl<T: ParsedSolution + Default + 'static> ParsedSolutionStatic for T {}
@@ -1,31 +1,122 @@
-use crate::solution::{Context, ProgressHandler, Solution};
+use crate::answers::{self, KnownAnswers, Verdict};
+use crate::helpers::is_wasm;
+use crate::inputs::{self, InputProvider, DEFAULT_PROFILE};
+use crate::solution::{
+    Answer, CancellationToken, Cancelled, Context, LogLevel, ProgressHandler, Solution,
+    SolutionType, TimedOut, DEFAULT_VARIANT,
+};
+use crate::solutions;
 use crate::util::{GenericResult, YearDay};
-use crate::{inputs, solutions};
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::{executor, Stream, StreamExt};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use std::cell::RefCell;
-use std::rc::Rc;
-use std::sync::{Arc, Mutex};
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe, PanicHookInfo};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::task::{Context as TaskContext, Poll};
 use std::thread;
 use std::time::Duration;
-use wasm_timer::SystemTime;
+use wasm_timer::{Instant, SystemTime};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ResultPack<T> {
     pub year_day: YearDay,
     pub part: Option<u8>,
     pub value: T,
     pub duration: Duration,
+    /// Comparison with the confirmed answer, only set for the results of parts.
+    #[serde(default)]
+    pub verdict: Option<Verdict>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LogMessage {
+    pub level: LogLevel,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum SolveProgress {
     Start(YearDay, String),
+    /// The solution has been initialized, including parsing its input.
+    /// The duration contains the time spent on initialization.
+    Initialized(ResultPack<()>),
     Error(String),
     Progress(ResultPack<f32>),
-    SuccessResult(ResultPack<String>),
+    Log(ResultPack<LogMessage>),
+    /// Intermediate 2D state of the solution, see [`Context::frame`].
+    Frame(ResultPack<Vec<String>>),
+    SuccessResult(ResultPack<Answer>),
     ErrorResult(ResultPack<String>),
+    Cancelled(ResultPack<()>),
+    /// The part ran out of its time budget. The value contains the budget.
+    Timeout(ResultPack<Duration>),
     Done(ResultPack<()>),
+    /// All days requested from [`run_many`] have finished.
+    Summary(SolveSummary),
+}
+
+/// Totals of the days run by [`run_many`].
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SolveSummary {
+    /// Number of days run, including the failed ones.
+    pub days: usize,
+    /// Number of parts solved successfully.
+    pub solved_parts: usize,
+    /// Sum of the runtimes of the days.
+    pub duration: Duration,
+    /// Wall-clock time of the whole run, shorter than the duration if days run concurrently.
+    pub elapsed: Duration,
+    /// Days and parts which failed with an error or timeout, with the reason.
+    pub failures: Vec<(YearDay, Option<u8>, String)>,
+    /// Days and parts with an answer differing from the confirmed one, also listed among the failures.
+    pub wrong_answers: Vec<(YearDay, u8)>,
+}
+impl SolveSummary {
+    /// Counts an event of the given day. The day itself is counted by its [`SolveProgress::Done`].
+    pub fn record(&mut self, year_day: YearDay, item: &SolveProgress) {
+        match item {
+            SolveProgress::SuccessResult(p) => {
+                self.solved_parts += 1;
+                if let Some(Verdict::Wrong(expected)) = &p.verdict {
+                    let reason = format!("wrong answer {}, expected {}", p.value, expected);
+                    self.failures.push((p.year_day, p.part, reason));
+                    self.wrong_answers.push((p.year_day, p.part.unwrap()));
+                }
+            }
+            SolveProgress::Error(err) => self.failures.push((year_day, None, err.to_owned())),
+            SolveProgress::ErrorResult(p) => {
+                self.failures.push((p.year_day, p.part, p.value.to_owned()))
+            }
+            SolveProgress::Timeout(p) => {
+                self.failures
+                    .push((p.year_day, p.part, "time limit exceeded".to_owned()))
+            }
+            SolveProgress::Done(p) => {
+                self.days += 1;
+                self.duration += p.duration;
+            }
+            _ => (),
+        }
+    }
+
+    /// Adds the totals of another summary, keeping the failures ordered by day and part.
+    pub fn merge(&mut self, other: SolveSummary) {
+        self.days += other.days;
+        self.solved_parts += other.solved_parts;
+        self.duration += other.duration;
+        self.elapsed += other.elapsed;
+        self.failures.extend(other.failures);
+        self.wrong_answers.extend(other.wrong_answers);
+        self.failures
+            .sort_by_key(|(year_day, part, _)| (*year_day, *part));
+        self.wrong_answers.sort();
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -34,7 +125,7 @@ pub enum Input {
     Custom(String),
 }
 
-pub trait SyncStream {
+pub trait SyncStream: Send {
     fn send(&mut self, item: SolveProgress);
     fn close(&mut self);
     fn next_items(&mut self) -> Option<Vec<SolveProgress>>;
@@ -70,53 +161,242 @@ impl SyncStream for LocalSyncStream {
     }
 }
 
+impl Default for LocalSyncStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stream backed by a channel, so that its events can be awaited instead of polled.
+/// The events are consumed through the [`ProgressReceiver`] taken from the stream.
+pub struct ChannelSyncStream {
+    tx: Option<UnboundedSender<SolveProgress>>,
+    rx: Option<ProgressReceiver>,
+}
+impl ChannelSyncStream {
+    pub fn new() -> ChannelSyncStream {
+        let (tx, rx) = mpsc::unbounded();
+        ChannelSyncStream {
+            tx: Some(tx),
+            rx: Some(ProgressReceiver(rx)),
+        }
+    }
+
+    /// Takes the receiving end of the stream. Returns None if it has already been taken.
+    /// The receiver is owned by the consumer, so waiting on it does not block the sender.
+    /// [`SyncStream::next_items`] returns None afterwards, as the events are read from the receiver.
+    pub fn take_receiver(&mut self) -> Option<ProgressReceiver> {
+        self.rx.take()
+    }
+}
+impl Default for ChannelSyncStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl SyncStream for ChannelSyncStream {
+    fn send(&mut self, item: SolveProgress) {
+        if let Some(tx) = &self.tx {
+            // The receiver may have been dropped by a consumer no longer interested in the events
+            _ = tx.unbounded_send(item);
+        }
+    }
+
+    fn close(&mut self) {
+        self.tx = None;
+    }
+
+    /// Returns the available items without blocking, or None if the stream is closed.
+    /// Always returns None after the receiver has been taken, as if the stream were closed,
+    /// so the events must then be read from the receiver instead.
+    fn next_items(&mut self) -> Option<Vec<SolveProgress>> {
+        self.rx.as_mut()?.try_items()
+    }
+}
+
+/// Receiving end of a [`ChannelSyncStream`], supporting both blocking and async consumption.
+pub struct ProgressReceiver(UnboundedReceiver<SolveProgress>);
+impl ProgressReceiver {
+    /// Blocks until the next item arrives. Returns None once the stream is closed and drained.
+    pub fn recv(&mut self) -> Option<SolveProgress> {
+        executor::block_on(self.0.next())
+    }
+
+    /// Blocks until at least one item arrives, then returns every available item.
+    /// Returns None once the stream is closed and drained.
+    pub fn recv_items(&mut self) -> Option<Vec<SolveProgress>> {
+        let first = self.recv()?;
+        let mut items = vec![first];
+        while let Ok(Some(item)) = self.0.try_next() {
+            items.push(item);
+        }
+        Some(items)
+    }
+
+    /// Returns the available items without blocking, or None if the stream is closed and drained.
+    fn try_items(&mut self) -> Option<Vec<SolveProgress>> {
+        let mut items = Vec::new();
+        loop {
+            match self.0.try_next() {
+                Ok(Some(item)) => items.push(item),
+                Ok(None) if items.is_empty() => return None,
+                Ok(None) | Err(_) => return Some(items),
+            }
+        }
+    }
+}
+impl Stream for ProgressReceiver {
+    type Item = SolveProgress;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0).poll_next(cx)
+    }
+}
+
 pub trait SolutionRunner<T: SyncStream> {
     fn run(&self, day: YearDay, input: Input) -> Arc<Mutex<T>>;
+
+    /// Runs the given days in order with their default inputs, see [`run_many`].
+    fn run_many(&self, days: Vec<YearDay>) -> Arc<Mutex<T>>;
+
+    /// Requests the cancellation of the solutions started by this runner.
+    fn cancel(&self);
+}
+
+/// Options for running a single solution.
+#[derive(Clone)]
+pub struct SolveOptions {
+    /// Maximum number of progress updates sent per second.
+    pub fps: f32,
+    /// Time budget for initialization and for each part. The deadline is checked
+    /// whenever the solution reports progress, so it is only enforced cooperatively.
+    pub part_time_limit: Option<Duration>,
+    /// Variant of the solution to run, see [`select_variant`].
+    pub variant: Option<String>,
+    pub cancellation_token: CancellationToken,
+    /// Least important level of the log messages sent by the solution.
+    pub log_level: LogLevel,
+    /// Send the frames of solutions supporting visualization. Frames are throttled by fps.
+    pub emit_frames: bool,
+    /// Providers looked up in order to resolve [`Input::Default`].
+    pub input_providers: Vec<Arc<dyn InputProvider>>,
+    /// Maximum number of days run concurrently by [`run_many`].
+    pub jobs: usize,
+    /// Confirmed answers the results of the default inputs are compared with.
+    pub answers: Arc<KnownAnswers>,
+    /// Profile of the inputs resolved for [`Input::Default`].
+    pub profile: String,
+}
+impl Default for SolveOptions {
+    fn default() -> Self {
+        Self {
+            fps: 20.1,
+            part_time_limit: None,
+            variant: None,
+            cancellation_token: Default::default(),
+            log_level: Default::default(),
+            emit_frames: false,
+            input_providers: inputs::default_providers(),
+            jobs: 1,
+            answers: Arc::new(answers::default_answers()),
+            profile: DEFAULT_PROFILE.to_owned(),
+        }
+    }
 }
-pub struct ThreadSolutionRunner {}
-impl SolutionRunner<LocalSyncStream> for ThreadSolutionRunner {
-    fn run(&self, year_day: YearDay, input: Input) -> Arc<Mutex<LocalSyncStream>> {
-        let stream = Arc::new(Mutex::new(LocalSyncStream::new()));
+
+#[derive(Default)]
+pub struct ThreadSolutionRunner {
+    pub options: SolveOptions,
+}
+impl<T: SyncStream + Default + 'static> SolutionRunner<T> for ThreadSolutionRunner {
+    fn run(&self, year_day: YearDay, input: Input) -> Arc<Mutex<T>> {
+        let stream = Arc::new(Mutex::new(T::default()));
         let stream_copy = Arc::clone(&stream);
-        thread::spawn(move || run_solution(year_day, input, stream_copy, 20.1));
+        let options = self.options.clone();
+        thread::spawn(move || run_solution(year_day, input, stream_copy, options));
 
         stream
     }
+
+    fn run_many(&self, days: Vec<YearDay>) -> Arc<Mutex<T>> {
+        let stream = Arc::new(Mutex::new(T::default()));
+        let stream_copy = Arc::clone(&stream);
+        let options = self.options.clone();
+        thread::spawn(move || run_many(&days, stream_copy, options));
+
+        stream
+    }
+
+    fn cancel(&self) {
+        self.options.cancellation_token.cancel();
+    }
 }
 
-pub fn run_solution<T: SyncStream + 'static>(
+pub fn run_solution<T: SyncStream + ?Sized + 'static>(
     year_day: YearDay,
     input: Input,
     tx: Arc<Mutex<T>>,
-    fps: f32,
+    options: SolveOptions,
+) {
+    run_solution_from(&solutions::create_map(), year_day, input, tx, options)
+}
+
+/// Same as [`run_solution`], but the solution is taken from the given ones instead of [`solutions::create_map`].
+pub fn run_solution_from<T: SyncStream + ?Sized + 'static>(
+    solutions: &HashMap<YearDay, Vec<SolutionType>>,
+    year_day: YearDay,
+    input: Input,
+    tx: Arc<Mutex<T>>,
+    options: SolveOptions,
 ) {
     let start = SystemTime::now();
+    // The confirmed answers belong to the default inputs
+    let expected = match input {
+        Input::Default => options.answers.parts(&year_day, &options.profile),
+        Input::Custom(_) => Default::default(),
+    };
     let raw_input = match input {
-        Input::Default => match inputs::get(&year_day) {
-            Some(input) => input.to_owned(),
-            None => {
-                return send_and_close(
-                    &tx,
-                    year_day,
-                    start,
-                    SolveProgress::Error("input not found".to_owned()),
-                );
+        Input::Default => {
+            match inputs::resolve(&options.input_providers, &year_day, &options.profile) {
+                Ok(input) => input,
+                Err(err) => {
+                    return send_and_close(
+                        &tx,
+                        year_day,
+                        start,
+                        SolveProgress::Error(err.to_string()),
+                    );
+                }
             }
-        },
+        }
         Input::Custom(input) => input,
     };
-    let current_part = Rc::new(RefCell::new(0));
+    let current_part = Arc::new(AtomicU8::new(0));
     let ctx = Context {
         raw_input,
-        progress_handler: RefCell::new(Box::new(SendOnProgress::new_with_fps(
-            fps,
+        progress_handler: Mutex::new(Box::new(SendOnProgress::new_with_fps(
+            options.fps,
             Arc::clone(&tx),
             year_day,
-            Rc::clone(&current_part),
+            Arc::clone(&current_part),
         ))),
+        cancellation_token: options.cancellation_token.clone(),
+        log_level: options.log_level,
+        emit_frames: options.emit_frames,
+        ..Default::default()
     };
-    let mut solution = match solutions::create_map().get(&year_day) {
-        Some(solutions) => solutions[0].create_new(),
+    let mut solution = match solutions.get(&year_day) {
+        Some(solutions) => match select_variant(solutions, options.variant.as_deref()) {
+            Some(solution_type) => solution_type.create_new(),
+            None => {
+                return send_and_close(
+                    &tx,
+                    year_day,
+                    start,
+                    SolveProgress::Error("solution variant not found".to_owned()),
+                );
+            }
+        },
         None => {
             return send_and_close(
                 &tx,
@@ -131,25 +411,186 @@ pub fn run_solution<T: SyncStream + 'static>(
         .unwrap()
         .send(SolveProgress::Start(year_day, solution.info().title));
     let start = SystemTime::now();
-    if let Err(err) = solution.init(&ctx) {
-        return send_and_close(
-            &tx,
+    set_deadline(&ctx, &options);
+    let init_step = RunningStep {
+        year_day,
+        part: None,
+        day_start: start,
+        part_start: start,
+    };
+    let init_result = catch_interrupt(init_step, || {
+        ctx.check_interrupted();
+        solution.init(&ctx)
+    });
+    match init_result {
+        Ok(Ok(_)) => tx
+            .lock()
+            .unwrap()
+            .send(SolveProgress::Initialized(ResultPack {
+                year_day,
+                part: None,
+                value: (),
+                duration: SystemTime::now().duration_since(start).unwrap_or_default(),
+                verdict: None,
+            })),
+        Ok(Err(err)) => {
+            return send_and_close(
+                &tx,
+                year_day,
+                start,
+                SolveProgress::Error(format!("Unable to initialize solution: {}", err).to_owned()),
+            );
+        }
+        Err(interrupt) => return interrupt_and_close(&tx, init_step, interrupt, &options),
+    }
+    for part in 1..=2 {
+        set_deadline(&ctx, &options);
+        current_part.store(part, Ordering::SeqCst);
+        let step = RunningStep {
             year_day,
-            start,
-            SolveProgress::Error(format!("Unable to initialize solution: {}", err).to_owned()),
-        );
+            part: Some(part),
+            day_start: start,
+            part_start: SystemTime::now(),
+        };
+        match solve_part(&mut solution, step, &ctx, &tx, &expected) {
+            Ok(Ok(_)) => (),
+            Ok(Err(_)) => return,
+            Err(interrupt) => return interrupt_and_close(&tx, step, interrupt, &options),
+        }
     }
-    if let Err(_) = solve_part(&mut solution, 1, start, &ctx, &tx, &current_part, year_day) {
-        return;
+
+    close(&tx, year_day, start);
+}
+
+/// Runs the given days with their default inputs, sending the events of all days to the same stream.
+/// Up to [`SolveOptions::jobs`] days run concurrently, so their events may be interleaved.
+/// Finishes with a [`SolveProgress::Summary`], unless a day is cancelled which closes the stream right away.
+pub fn run_many<T: SyncStream + 'static>(
+    days: &[YearDay],
+    tx: Arc<Mutex<T>>,
+    options: SolveOptions,
+) {
+    run_many_with(days, tx, options, |year_day, tx, options| {
+        run_solution(year_day, Input::Default, tx, options)
+    });
+}
+
+/// Same as [`run_many`], but each day is run by the given function instead of [`run_solution`].
+/// The function must send the events of the day to the given stream, finishing with [`SolveProgress::Done`]
+/// or [`SolveProgress::Cancelled`].
+pub fn run_many_with<T, F>(days: &[YearDay], tx: Arc<Mutex<T>>, options: SolveOptions, run_day: F)
+where
+    T: SyncStream + 'static,
+    F: Fn(YearDay, Arc<Mutex<dyn SyncStream>>, SolveOptions) + Sync,
+{
+    let start = SystemTime::now();
+    let queue = Mutex::new(days.iter().copied().collect::<VecDeque<_>>());
+    let jobs = options.jobs.clamp(1, days.len().max(1));
+    let batches = match jobs {
+        // Avoid spawning threads, as the WASM worker cannot do that
+        1 => vec![run_queue(&queue, &tx, &options, &run_day)],
+        _ => thread::scope(|scope| {
+            let workers = (0..jobs)
+                .map(|_| scope.spawn(|| run_queue(&queue, &tx, &options, &run_day)))
+                .collect_vec();
+            workers.into_iter().map(|w| w.join().unwrap()).collect_vec()
+        }),
+    };
+
+    let mut tx = tx.lock().unwrap();
+    if batches.iter().any(|(_, cancelled)| *cancelled) {
+        return tx.close();
     }
-    if let Err(_) = solve_part(&mut solution, 2, start, &ctx, &tx, &current_part, year_day) {
-        return;
+    let mut summary = SolveSummary {
+        elapsed: SystemTime::now().duration_since(start).unwrap_or_default(),
+        ..Default::default()
+    };
+    for (batch, _) in batches {
+        summary.merge(batch);
     }
+    tx.send(SolveProgress::Summary(summary));
+    tx.close();
+}
 
-    close(&tx, year_day, start);
+/// Runs the days taken from the queue one by one until it is empty or a day is cancelled.
+/// Returns the summary of the days run, and whether the last one was cancelled.
+fn run_queue<T, F>(
+    queue: &Mutex<VecDeque<YearDay>>,
+    tx: &Arc<Mutex<T>>,
+    options: &SolveOptions,
+    run_day: &F,
+) -> (SolveSummary, bool)
+where
+    T: SyncStream + 'static,
+    F: Fn(YearDay, Arc<Mutex<dyn SyncStream>>, SolveOptions),
+{
+    let batch_tx = Arc::new(Mutex::new(BatchStream {
+        tx: Arc::clone(tx),
+        year_day: Default::default(),
+        summary: Default::default(),
+        cancelled: false,
+    }));
+    loop {
+        let year_day = match queue.lock().unwrap().pop_front() {
+            Some(year_day) => year_day,
+            None => break,
+        };
+        batch_tx.lock().unwrap().year_day = year_day;
+        run_day(year_day, Arc::clone(&batch_tx) as _, options.clone());
+        if batch_tx.lock().unwrap().cancelled {
+            break;
+        }
+    }
+
+    let batch_tx = batch_tx.lock().unwrap();
+    (batch_tx.summary.clone(), batch_tx.cancelled)
+}
+
+/// Forwards the events of a single day to the stream of [`run_many`], keeping it open between days.
+struct BatchStream<T: SyncStream> {
+    tx: Arc<Mutex<T>>,
+    /// Day being run, as [`SolveProgress::Error`] does not carry it.
+    year_day: YearDay,
+    summary: SolveSummary,
+    cancelled: bool,
+}
+impl<T: SyncStream> SyncStream for BatchStream<T> {
+    fn send(&mut self, item: SolveProgress) {
+        if let SolveProgress::Cancelled(_) = item {
+            self.cancelled = true;
+        }
+        self.summary.record(self.year_day, &item);
+        self.tx.lock().unwrap().send(item);
+    }
+
+    fn close(&mut self) {}
+
+    fn next_items(&mut self) -> Option<Vec<SolveProgress>> {
+        None
+    }
 }
 
-fn send_and_close<T: SyncStream>(
+/// Selects the solution with the given variant label.
+/// Without a label the default variant is preferred, falling back to the first one.
+pub fn select_variant<'a>(
+    solutions: &'a [SolutionType],
+    variant: Option<&str>,
+) -> Option<&'a SolutionType> {
+    match variant {
+        Some(variant) => solutions.iter().find(|s| s.info.variant == variant),
+        None => solutions
+            .iter()
+            .find(|s| s.info.variant == DEFAULT_VARIANT)
+            .or(solutions.first()),
+    }
+}
+
+fn set_deadline(ctx: &Context, options: &SolveOptions) {
+    *ctx.deadline.lock().unwrap() = options.part_time_limit.map(|limit| Instant::now() + limit);
+}
+
+/// Sends the given event followed by [`SolveProgress::Done`], then closes the stream.
+pub fn send_and_close<T: SyncStream + ?Sized>(
     tx: &Arc<Mutex<T>>,
     day: YearDay,
     start: SystemTime,
@@ -159,33 +600,221 @@ fn send_and_close<T: SyncStream>(
     close(tx, day, start);
 }
 
-fn close<T: SyncStream>(tx: &Arc<Mutex<T>>, day: YearDay, start: SystemTime) {
+fn close<T: SyncStream + ?Sized>(tx: &Arc<Mutex<T>>, day: YearDay, start: SystemTime) {
     let mut tx = tx.lock().unwrap();
     tx.send(SolveProgress::Done(ResultPack {
         year_day: day,
         part: None,
         value: (),
         duration: SystemTime::now().duration_since(start).unwrap_or_default(),
+        verdict: None,
     }));
     tx.close();
 }
 
-fn solve_part<T: SyncStream>(
+/// Reports the interruption of a solution.
+/// Cancellation closes the stream right away, while a timeout or a panic finishes the day normally.
+fn interrupt_and_close<T: SyncStream + ?Sized>(
+    tx: &Arc<Mutex<T>>,
+    step: RunningStep,
+    interrupt: Interrupt,
+    options: &SolveOptions,
+) {
+    let RunningStep {
+        year_day: day,
+        part,
+        day_start: start,
+        ..
+    } = step;
+    let duration = SystemTime::now().duration_since(start).unwrap_or_default();
+    match interrupt {
+        Interrupt::Cancelled => {
+            let mut tx = tx.lock().unwrap();
+            tx.send(SolveProgress::Cancelled(ResultPack {
+                year_day: day,
+                part,
+                value: (),
+                duration,
+                verdict: None,
+            }));
+            tx.close();
+        }
+        Interrupt::TimedOut => {
+            report_timeout(tx, step, options.part_time_limit.unwrap_or_default())
+        }
+        Interrupt::Panicked(message) => report_panic(tx, step, &message),
+    }
+}
+
+enum Interrupt {
+    Cancelled,
+    TimedOut,
+    /// The solution panicked, with the message and location of the panic.
+    Panicked(String),
+}
+
+/// Solution step running on the current thread, used to report its panic.
+#[derive(Clone, Copy)]
+pub struct RunningStep {
+    pub year_day: YearDay,
+    /// The part being solved, or None during initialization.
+    pub part: Option<u8>,
+    pub day_start: SystemTime,
+    pub part_start: SystemTime,
+}
+
+thread_local! {
+    static RUNNING_STEP: Cell<Option<RunningStep>> = const { Cell::new(None) };
+    /// Message of the last panic of the running step, recorded by the panic hook.
+    static STEP_PANIC: Cell<Option<String>> = const { Cell::new(None) };
+}
+
+/// Number of solution steps running on any thread.
+static RUNNING_STEPS: AtomicUsize = AtomicUsize::new(0);
+/// Payload messages and descriptions of the panics of rayon worker threads while solution steps are running.
+/// Rayon passes the payload on to the thread that started the parallel work without calling the panic hook,
+/// so the step receiving it looks up the description of its panic here.
+static WORKER_PANICS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Returns the solution step running on the current thread, if any.
+pub fn running_step() -> Option<RunningStep> {
+    RUNNING_STEP.with(|step| step.get())
+}
+
+/// Describes a panic with its message and location.
+pub fn panic_message(info: &PanicHookInfo) -> String {
+    let message = payload_message(info.payload());
+    match info.location() {
+        Some(location) => format!("panicked at {}: {}", location, message),
+        None => format!("panicked: {}", message),
+    }
+}
+
+/// Returns the message of a panic payload.
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.to_owned(),
+        _ => "unknown panic".to_owned(),
+    }
+}
+
+/// Describes a panic propagated to a solution step without calling the panic hook,
+/// using the description recorded for the same panic by a rayon worker thread if any.
+fn propagated_panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = payload_message(payload);
+    let mut worker_panics = WORKER_PANICS.lock().unwrap();
+    match worker_panics.iter().position(|(m, _)| *m == message) {
+        Some(index) => worker_panics.remove(index).1,
+        None => format!("panicked: {}", message),
+    }
+}
+
+/// Reports the panic of a solution step as an error result of its part, then finishes the day.
+pub fn report_panic<T: SyncStream + ?Sized>(tx: &Arc<Mutex<T>>, step: RunningStep, message: &str) {
+    let event = match step.part {
+        Some(part) => SolveProgress::ErrorResult(ResultPack {
+            year_day: step.year_day,
+            part: Some(part),
+            value: message.to_owned(),
+            duration: SystemTime::now()
+                .duration_since(step.part_start)
+                .unwrap_or_default(),
+            verdict: None,
+        }),
+        None => SolveProgress::Error(format!("Unable to initialize solution: {}", message)),
+    };
+    send_and_close(tx, step.year_day, step.day_start, event);
+}
+
+/// Reports that a solution step exceeded the given part time limit, then finishes the day.
+pub fn report_timeout<T: SyncStream + ?Sized>(
+    tx: &Arc<Mutex<T>>,
+    step: RunningStep,
+    limit: Duration,
+) {
+    let event = SolveProgress::Timeout(ResultPack {
+        year_day: step.year_day,
+        part: step.part,
+        value: limit,
+        duration: SystemTime::now()
+            .duration_since(step.part_start)
+            .unwrap_or_default(),
+        verdict: None,
+    });
+    send_and_close(tx, step.year_day, step.day_start, event);
+}
+
+/// Records the panics of solution steps instead of printing them, so they can be reported as errors.
+/// While steps are running, the panics of rayon worker threads are recorded as well, as they are raised by
+/// the parallel code of a step and passed on to it, see [`WORKER_PANICS`].
+/// Other panics, like the ones of threads spawned directly, are passed to the previous hook.
+/// Panics cannot be caught in WASM, where the hook is left as is.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    if is_wasm() {
+        return;
+    }
+    INSTALL.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if running_step().is_some() {
+                STEP_PANIC.with(|p| p.set(Some(panic_message(info))));
+            } else if RUNNING_STEPS.load(Ordering::SeqCst) > 0
+                && rayon::current_thread_index().is_some()
+            {
+                let message = (payload_message(info.payload()), panic_message(info));
+                WORKER_PANICS.lock().unwrap().push(message);
+            } else {
+                previous_hook(info);
+            }
+        }));
+    });
+}
+
+/// Calls the given solution step, converting the unwind caused by [`Context::check_interrupted`]
+/// or by a panic into an error.
+fn catch_interrupt<R, F: FnOnce() -> R>(step: RunningStep, f: F) -> Result<R, Interrupt> {
+    install_panic_hook();
+    RUNNING_STEP.with(|s| s.set(Some(step)));
+    RUNNING_STEPS.fetch_add(1, Ordering::SeqCst);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    RUNNING_STEPS.fetch_sub(1, Ordering::SeqCst);
+    RUNNING_STEP.with(|s| s.set(None));
+    match result {
+        Ok(result) => Ok(result),
+        Err(payload) if payload.is::<Cancelled>() => Err(Interrupt::Cancelled),
+        Err(payload) if payload.is::<TimedOut>() => Err(Interrupt::TimedOut),
+        Err(payload) => Err(Interrupt::Panicked(
+            STEP_PANIC
+                .with(|p| p.take())
+                .unwrap_or_else(|| propagated_panic_message(payload.as_ref())),
+        )),
+    }
+}
+
+/// Solves the given part and reports its result.
+/// Returns an error if the part was interrupted before finishing.
+fn solve_part<T: SyncStream + ?Sized>(
     solution: &mut Box<dyn Solution>,
-    part: u8,
-    global_start: SystemTime,
+    step: RunningStep,
     ctx: &Context,
     tx: &Arc<Mutex<T>>,
-    current_part: &Rc<RefCell<u8>>,
-    day: YearDay,
-) -> GenericResult<String> {
-    let start = SystemTime::now();
-    *current_part.borrow_mut() = part;
-    let result = match part {
-        1 => solution.part1(ctx),
-        2 => solution.part2(ctx),
-        _ => Err("Invalid part!".into()),
-    };
+    expected: &BTreeMap<u8, String>,
+) -> Result<GenericResult<Answer>, Interrupt> {
+    let (day, global_start, start) = (step.year_day, step.day_start, step.part_start);
+    let part = step.part.unwrap_or_default();
+    let result = catch_interrupt(step, || {
+        ctx.check_interrupted();
+        match part {
+            1 => solution.part1(ctx),
+            2 => solution.part2(ctx),
+            _ => Err("Invalid part!".into()),
+        }
+    })?;
     let duration = SystemTime::now().duration_since(start).unwrap_or_default();
     match &result {
         Ok(result) => tx
@@ -196,6 +825,10 @@ fn solve_part<T: SyncStream>(
                 part: Some(part),
                 value: result.to_owned(),
                 duration,
+                verdict: Some(answers::check(
+                    expected.get(&part).map(|a| a.as_str()),
+                    result,
+                )),
             })),
         Err(err) => {
             send_and_close(
@@ -207,34 +840,33 @@ fn solve_part<T: SyncStream>(
                     part: Some(part),
                     value: err.to_string(),
                     duration,
+                    verdict: None,
                 }),
             );
         }
     };
 
-    result
+    Ok(result)
 }
 
-pub struct SendOnProgress<T: SyncStream> {
+pub struct SendOnProgress<T: SyncStream + ?Sized> {
     tx: Arc<Mutex<T>>,
     min_duration_between_updates: Duration,
     start: SystemTime,
     last_update: SystemTime,
-    current_part: Rc<RefCell<u8>>,
+    last_frame: SystemTime,
+    current_part: Arc<AtomicU8>,
     day: YearDay,
 }
-impl<T: SyncStream> SendOnProgress<T> {
-    pub fn new(
-        tx: Arc<Mutex<T>>,
-        day: YearDay,
-        current_part: Rc<RefCell<u8>>,
-    ) -> SendOnProgress<T> {
+impl<T: SyncStream + ?Sized> SendOnProgress<T> {
+    pub fn new(tx: Arc<Mutex<T>>, day: YearDay, current_part: Arc<AtomicU8>) -> SendOnProgress<T> {
         SendOnProgress {
             tx,
             day,
             min_duration_between_updates: Duration::from_millis(0),
             start: SystemTime::now(),
             last_update: SystemTime::UNIX_EPOCH,
+            last_frame: SystemTime::UNIX_EPOCH,
             current_part,
         }
     }
@@ -243,31 +875,71 @@ impl<T: SyncStream> SendOnProgress<T> {
         fps: f32,
         tx: Arc<Mutex<T>>,
         day: YearDay,
-        current_part: Rc<RefCell<u8>>,
+        current_part: Arc<AtomicU8>,
     ) -> SendOnProgress<T> {
         let mut sop = SendOnProgress::new(tx, day, current_part);
         sop.min_duration_between_updates = Duration::from_millis((1000.0 / fps.max(0.001)) as u64);
         return sop;
     }
-}
-impl<T: SyncStream> ProgressHandler for SendOnProgress<T> {
-    fn on_progress(&mut self, value: f32) {
+
+    /// True, if enough time has passed since the given update according to the fps setting.
+    fn is_due(&self, last_update: SystemTime) -> bool {
         let elapsed_since_last_update = SystemTime::now()
-            .duration_since(self.last_update)
+            .duration_since(last_update)
             .unwrap_or_default();
-        if elapsed_since_last_update >= self.min_duration_between_updates {
+        elapsed_since_last_update >= self.min_duration_between_updates
+    }
+}
+impl<T: SyncStream + ?Sized> ProgressHandler for SendOnProgress<T> {
+    fn on_progress(&mut self, value: f32) {
+        if self.is_due(self.last_update) {
             self.tx
                 .lock()
                 .unwrap()
                 .send(SolveProgress::Progress(ResultPack {
                     year_day: self.day,
-                    part: Some(*self.current_part.borrow()),
+                    part: Some(self.current_part.load(Ordering::SeqCst)),
                     value,
                     duration: SystemTime::now()
                         .duration_since(self.start)
                         .unwrap_or_default(),
+                    verdict: None,
                 }));
             self.last_update = SystemTime::now();
         }
     }
+    fn accepts_frame(&self) -> bool {
+        self.is_due(self.last_frame)
+    }
+
+    fn on_frame(&mut self, lines: Vec<String>) {
+        self.tx
+            .lock()
+            .unwrap()
+            .send(SolveProgress::Frame(ResultPack {
+                year_day: self.day,
+                part: Some(self.current_part.load(Ordering::SeqCst)),
+                value: lines,
+                duration: SystemTime::now()
+                    .duration_since(self.start)
+                    .unwrap_or_default(),
+                verdict: None,
+            }));
+        self.last_frame = SystemTime::now();
+    }
+
+    fn on_log(&mut self, level: LogLevel, message: &str) {
+        self.tx.lock().unwrap().send(SolveProgress::Log(ResultPack {
+            year_day: self.day,
+            part: Some(self.current_part.load(Ordering::SeqCst)).filter(|&p| p > 0),
+            value: LogMessage {
+                level,
+                message: message.to_owned(),
+            },
+            duration: SystemTime::now()
+                .duration_since(self.start)
+                .unwrap_or_default(),
+            verdict: None,
+        }));
+    }
 }
@@ -1,42 +1,258 @@
-use crate::util::YearDay;
+use crate::core::file_util::{input_file_name, year_directory_name, INPUT_PATH};
+use crate::helpers::is_wasm;
+use crate::util::{day_str, GenericResult, YearDay};
 use include_dir::{include_dir, Dir};
+use itertools::Itertools;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::fmt::Debug;
-use std::{collections::HashMap, ffi::OsStr, str::FromStr};
-
-static INPUT_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/input");
-static INPUT_CACHE: Lazy<HashMap<YearDay, String>> = Lazy::new(|| create_input_map());
-
-fn capture_filename_number<T: FromStr>(re: &Regex, path: Option<&OsStr>) -> T
-where
-    <T as FromStr>::Err: Debug,
-{
-    re.captures(path.unwrap().to_str().unwrap())
-        .unwrap()
-        .get(1)
-        .unwrap()
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::{collections::HashMap, fs, str::FromStr};
+
+/// Profile of the inputs stored as `yearXXXX/dayXX.txt`. Other profiles are stored as `yearXXXX/dayXX/<profile>.txt`.
+pub const DEFAULT_PROFILE: &str = "default";
+
+pub(crate) static INPUT_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/input");
+static INPUT_CACHE: Lazy<HashMap<(YearDay, String), String>> =
+    Lazy::new(|| create_input_map(&INPUT_DIR));
+
+/// Returns the number in a file name matching the pattern, or None for unrelated files such as `notes.txt`.
+fn capture_filename_number<T: FromStr>(re: &Regex, path: &Path) -> Option<T> {
+    re.captures(path.file_name()?.to_str()?)?
+        .get(1)?
         .as_str()
         .parse()
-        .unwrap()
+        .ok()
 }
 
-fn create_input_map() -> HashMap<YearDay, String> {
-    let year_re = Regex::new(r"year(\d+)").unwrap();
-    let day_re = Regex::new(r"day(\d+).txt").unwrap();
+/// Maps the inputs of the directory by day and profile, skipping the files and directories not named after them.
+pub(crate) fn create_input_map(input_dir: &Dir) -> HashMap<(YearDay, String), String> {
+    let year_re = Regex::new(r"^year(\d+)$").unwrap();
+    let day_re = Regex::new(r"^day(\d+)\.txt$").unwrap();
+    let day_dir_re = Regex::new(r"^day(\d+)$").unwrap();
     let mut cache = HashMap::new();
-    for dir in INPUT_DIR.dirs() {
-        let year = capture_filename_number(&year_re, dir.path().file_name());
+    let years = input_dir
+        .dirs()
+        .filter_map(|dir| Some((capture_filename_number(&year_re, dir.path())?, dir)));
+    for (year, dir) in years {
         for file in dir.files() {
-            let day = capture_filename_number(&day_re, file.path().file_name());
+            if let (Some(day), Some(input)) = (
+                capture_filename_number(&day_re, file.path()),
+                file.contents_utf8(),
+            ) {
+                cache.insert(
+                    (YearDay::new(year, day), DEFAULT_PROFILE.to_owned()),
+                    input.to_owned(),
+                );
+            }
+        }
+        let days = dir
+            .dirs()
+            .filter_map(|dir| Some((capture_filename_number(&day_dir_re, dir.path())?, dir)));
+        for (day, day_dir) in days {
             let yd = YearDay::new(year, day);
-
-            cache.insert(yd, file.contents_utf8().unwrap().to_owned());
+            for file in day_dir.files() {
+                if let (Some(profile), Some(input)) =
+                    (profile_name(file.path()), file.contents_utf8())
+                {
+                    cache.insert((yd, profile), input.to_owned());
+                }
+            }
         }
     }
     cache
 }
 
-pub fn get(year_day: &YearDay) -> Option<&String> {
-    INPUT_CACHE.get(year_day)
+/// Returns the profile of a `yearXXXX/dayXX/<profile>.txt` input file.
+fn profile_name(path: &Path) -> Option<String> {
+    match path.extension().and_then(|x| x.to_str()) {
+        Some("txt") => path
+            .file_stem()
+            .and_then(|x| x.to_str())
+            .map(|x| x.to_owned()),
+        _ => None,
+    }
+}
+
+/// Returns the input of the profile embedded at compile time.
+pub fn get(year_day: &YearDay, profile: &str) -> Option<&'static String> {
+    INPUT_CACHE.get(&(*year_day, profile.to_owned()))
+}
+
+/// Returns the profiles having an input embedded at compile time for the given day.
+pub fn profiles(year_day: &YearDay) -> Vec<String> {
+    INPUT_CACHE
+        .keys()
+        .filter(|(yd, _)| yd == year_day)
+        .map(|(_, profile)| profile.to_owned())
+        .collect_vec()
+}
+
+/// Source of puzzle inputs.
+pub trait InputProvider: Send + Sync {
+    /// Returns the input of the given day and profile, or None if this provider does not have it.
+    fn get(&self, year_day: &YearDay, profile: &str) -> GenericResult<Option<String>>;
+
+    /// Returns the profiles this provider has a separate input for on the given day.
+    fn profiles(&self, _year_day: &YearDay) -> GenericResult<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// Describes where the inputs are looked up, used in error messages.
+    fn description(&self) -> String;
+}
+
+/// Inputs embedded from `aoc-lib/input` at compile time.
+pub struct EmbeddedInputs;
+impl InputProvider for EmbeddedInputs {
+    fn get(&self, year_day: &YearDay, profile: &str) -> GenericResult<Option<String>> {
+        Ok(get(year_day, profile).cloned())
+    }
+
+    fn profiles(&self, year_day: &YearDay) -> GenericResult<Vec<String>> {
+        Ok(profiles(year_day))
+    }
+
+    fn description(&self) -> String {
+        "embedded inputs".to_owned()
+    }
+}
+
+/// Inputs read at runtime from a directory with the same `yearXXXX/dayXX.txt` layout as `aoc-lib/input`.
+pub struct DirectoryInputs {
+    pub path: PathBuf,
+}
+impl InputProvider for DirectoryInputs {
+    fn get(&self, year_day: &YearDay, profile: &str) -> GenericResult<Option<String>> {
+        let path = self
+            .path
+            .join(year_directory_name(year_day.year))
+            .join(input_file_name(year_day.day, profile));
+        match fs::read_to_string(path) {
+            Ok(input) => Ok(Some(input)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn profiles(&self, year_day: &YearDay) -> GenericResult<Vec<String>> {
+        let year_dir = self.path.join(year_directory_name(year_day.year));
+        let mut profiles = Vec::new();
+        if year_dir
+            .join(input_file_name(year_day.day, DEFAULT_PROFILE))
+            .is_file()
+        {
+            profiles.push(DEFAULT_PROFILE.to_owned());
+        }
+        match fs::read_dir(year_dir.join(format!("day{}", day_str(year_day.day)))) {
+            Ok(entries) => {
+                for entry in entries {
+                    profiles.extend(profile_name(&entry?.path()));
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+            Err(err) => Err(err)?,
+        }
+
+        Ok(profiles)
+    }
+
+    fn description(&self) -> String {
+        format!("directory '{}'", self.path.display())
+    }
+}
+
+/// The same input file for every day.
+pub struct FileInput {
+    pub path: PathBuf,
+}
+impl InputProvider for FileInput {
+    fn get(&self, _year_day: &YearDay, _profile: &str) -> GenericResult<Option<String>> {
+        Ok(Some(fs::read_to_string(&self.path).map_err(|err| {
+            format!("could not read '{}': {}", self.path.display(), err)
+        })?))
+    }
+
+    fn description(&self) -> String {
+        format!("file '{}'", self.path.display())
+    }
+}
+
+/// The standard input for every day. It is read once and reused afterwards.
+#[derive(Default)]
+pub struct StdinInput {
+    input: Mutex<Option<String>>,
+}
+impl InputProvider for StdinInput {
+    fn get(&self, _year_day: &YearDay, _profile: &str) -> GenericResult<Option<String>> {
+        let mut input = self.input.lock().unwrap();
+        if input.is_none() {
+            let mut buf = String::new();
+            io::stdin().read_to_string(&mut buf)?;
+            *input = Some(buf);
+        }
+
+        Ok(input.clone())
+    }
+
+    fn description(&self) -> String {
+        "standard input".to_owned()
+    }
+}
+
+/// Looks up inputs in the input directory first, so new inputs can be used without rebuilding,
+/// then falls back to the embedded inputs. Only the embedded inputs are available in WASM.
+pub fn default_providers() -> Vec<Arc<dyn InputProvider>> {
+    match is_wasm() {
+        true => vec![Arc::new(EmbeddedInputs)],
+        false => vec![
+            Arc::new(DirectoryInputs {
+                path: PathBuf::from(INPUT_PATH),
+            }),
+            Arc::new(EmbeddedInputs),
+        ],
+    }
+}
+
+/// Returns the input of the profile from the first provider having it.
+pub fn resolve(
+    providers: &[Arc<dyn InputProvider>],
+    year_day: &YearDay,
+    profile: &str,
+) -> GenericResult<String> {
+    for provider in providers {
+        if let Some(input) = provider.get(year_day, profile)? {
+            return Ok(input);
+        }
+    }
+
+    let profile = match profile {
+        DEFAULT_PROFILE => String::new(),
+        profile => format!(" profile '{}'", profile),
+    };
+    Err(format!(
+        "input not found for {} day {}{} in: {}",
+        year_day.year,
+        year_day.day,
+        profile,
+        providers.iter().map(|p| p.description()).join(", ")
+    ))?
+}
+
+/// Returns the profiles having an input for the given day in any of the providers, the default profile first.
+pub fn resolve_profiles(
+    providers: &[Arc<dyn InputProvider>],
+    year_day: &YearDay,
+) -> GenericResult<Vec<String>> {
+    let mut profiles = Vec::new();
+    for provider in providers {
+        profiles.extend(provider.profiles(year_day)?);
+    }
+
+    Ok(profiles
+        .into_iter()
+        .unique()
+        .sorted_by_key(|p| (p != DEFAULT_PROFILE, p.to_owned()))
+        .collect_vec())
 }
@@ -2,10 +2,14 @@
 pub mod core {
     pub mod file_util;
     pub mod helpers;
+    #[cfg(not(target_arch = "wasm32"))]
+    pub mod hot_reload;
+    pub mod recording;
     pub mod solution;
     pub mod solution_runner;
     pub mod util;
 }
+pub mod answers;
 pub mod inputs;
 pub mod solutions;
 
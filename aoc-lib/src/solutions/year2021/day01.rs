@@ -16,7 +16,7 @@ impl Solution for Day01 {
                 .skip(1)
                 .fold(0, |a, (i, x)| if &items[i - 1] < x { a + 1 } else { a });
 
-        Ok(count.to_string())
+        Ok(count.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -29,6 +29,6 @@ impl Solution for Day01 {
             .filter(|&x| x[0] < x[1])
             .count();
 
-        Ok(count.to_string())
+        Ok(count.into())
     }
 }
@@ -22,12 +22,12 @@ impl Solution for Day12 {
 
     fn part1(&mut self, _ctx: &Context) -> SolutionResult {
         let path_count = self.count_paths(self.start_id, &mut Vec::new(), 0);
-        Ok(path_count.to_string())
+        Ok(path_count.into())
     }
 
     fn part2(&mut self, _ctx: &Context) -> SolutionResult {
         let path_count = self.count_paths(self.start_id, &mut Vec::new(), 1);
-        Ok(path_count.to_string())
+        Ok(path_count.into())
     }
 }
 impl Day12 {
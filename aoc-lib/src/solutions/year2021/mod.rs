@@ -1,7 +1,7 @@
 // --- This file was auto-generated by build.rs ---
 
 #[allow(unused_imports)]
-use crate::solution::{SolutionStatic, SolutionType};
+use crate::solution::{ParsedSolutionStatic, SolutionStatic, SolutionType};
 use crate::util::YearDay;
 use std::collections::HashMap;
 
@@ -16,7 +16,7 @@ impl Solution for Day01 {
             .max()
             .unwrap_or(0);
 
-        Ok(max.to_string())
+        Ok(max.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -28,6 +28,6 @@ impl Solution for Day01 {
             .take(3)
             .sum::<i32>();
 
-        Ok(max.to_string())
+        Ok(max.into())
     }
 }
@@ -16,7 +16,7 @@ impl Solution for Day02 {
             .into_iter()
             .fold(0, |acc, (a, b)| acc + b + 1 + calc_points(a, b));
 
-        Ok(points.to_string())
+        Ok(points.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -25,7 +25,7 @@ impl Solution for Day02 {
             .map(|(a, b)| (a, (a + b + 2) % 3))
             .fold(0, |acc, (a, b)| acc + b + 1 + calc_points(a, b));
 
-        Ok(points.to_string())
+        Ok(points.into())
     }
 }
 
@@ -27,7 +27,7 @@ impl Solution for Day03 {
             sum += get_priority(common)?;
         }
 
-        Ok(sum.to_string())
+        Ok(sum.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -46,7 +46,7 @@ impl Solution for Day03 {
             sum += get_priority(common)?;
         }
 
-        Ok(sum.to_string())
+        Ok(sum.into())
     }
 }
 
@@ -14,7 +14,7 @@ impl Solution for Day04 {
             .filter(|(a1, a2, b1, b2)| (a1 <= b1 && a2 >= b2) || (b1 <= a1 && b2 >= a2))
             .count();
 
-        Ok(overlap_count.to_string())
+        Ok(overlap_count.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -23,7 +23,7 @@ impl Solution for Day04 {
             .filter(|(a1, a2, b1, b2)| (a1 <= b1 && b1 <= a2) || (b1 <= a1 && a1 <= b2))
             .count();
 
-        Ok(overlap_count.to_string())
+        Ok(overlap_count.into())
     }
 }
 
@@ -1,17 +1,23 @@
-use crate::solution::*;
+use crate::{solution::*, util::GenericResult};
 use itertools::Itertools;
 use regex::Regex;
 
 #[derive(Default)]
 pub struct Day05;
-impl Solution for Day05 {
+impl ParsedSolution for Day05 {
+    type Parsed = (Vec<Vec<char>>, Vec<(usize, usize, usize)>);
+
     fn info(&self) -> SolutionInfo {
         Title::new(2022, 5, "Supply Stacks")
     }
 
-    fn part1(&mut self, ctx: &Context) -> SolutionResult {
-        let (mut stacks, steps) = parse_input(&ctx);
-        for (count, from, to) in steps {
+    fn parse(&mut self, ctx: &Context) -> GenericResult<Self::Parsed> {
+        Ok(parse_input(ctx))
+    }
+
+    fn part1(&mut self, (stacks, steps): &Self::Parsed, _ctx: &Context) -> SolutionResult {
+        let mut stacks = stacks.clone();
+        for &(count, from, to) in steps {
             for _ in 0..count {
                 let item = stacks[from].pop().unwrap();
                 stacks[to].push(item);
@@ -19,12 +25,12 @@ impl Solution for Day05 {
         }
 
         let top: String = stacks.into_iter().map(|mut s| s.pop().unwrap()).collect();
-        Ok(top)
+        Ok(top.into())
     }
 
-    fn part2(&mut self, ctx: &Context) -> SolutionResult {
-        let (mut stacks, steps) = parse_input(&ctx);
-        for (count, from, to) in steps {
+    fn part2(&mut self, (stacks, steps): &Self::Parsed, _ctx: &Context) -> SolutionResult {
+        let mut stacks = stacks.clone();
+        for &(count, from, to) in steps {
             let target_height = stacks[to].len();
             for _ in 0..count {
                 let item = stacks[from].pop().unwrap();
@@ -33,7 +39,7 @@ impl Solution for Day05 {
         }
 
         let top: String = stacks.into_iter().map(|mut s| s.pop().unwrap()).collect();
-        Ok(top)
+        Ok(top.into())
     }
 }
 
@@ -11,12 +11,12 @@ impl Solution for Day06 {
 
     fn part1(&mut self, ctx: &Context) -> SolutionResult {
         let index = find_marker(&ctx, 4);
-        Ok(index.to_string())
+        Ok(index.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
         let index = find_marker(&ctx, 14);
-        Ok(index.to_string())
+        Ok(index.into())
     }
 }
 
@@ -21,7 +21,7 @@ impl Solution for Day07 {
             .filter(|&size| size <= 100000)
             .sum::<u64>();
 
-        Ok(sum_of_small_directories.to_string())
+        Ok(sum_of_small_directories.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -38,7 +38,7 @@ impl Solution for Day07 {
             .find(|&size| size >= required_space)
             .ok_or("no directory is large enough")?;
 
-        Ok(size_of_dir_to_delete.to_string())
+        Ok(size_of_dir_to_delete.into())
     }
 }
 
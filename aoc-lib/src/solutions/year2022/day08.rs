@@ -52,7 +52,7 @@ impl Solution for Day08 {
             })
             .sum::<i32>();
 
-        Ok(visible_count.to_string())
+        Ok(visible_count.into())
     }
 
     fn part2(&mut self, _ctx: &Context) -> SolutionResult {
@@ -66,7 +66,7 @@ impl Solution for Day08 {
             })
             .max()
             .ok_or("map is empty")?;
-        Ok(max_score.to_string())
+        Ok(max_score.into())
     }
 }
 
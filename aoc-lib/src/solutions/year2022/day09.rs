@@ -12,12 +12,12 @@ impl Solution for Day09 {
 
     fn part1(&mut self, ctx: &Context) -> SolutionResult {
         let visited = simulate_rope(2, ctx);
-        Ok(visited.len().to_string())
+        Ok(visited.len().into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
         let visited = simulate_rope(10, ctx);
-        Ok(visited.len().to_string())
+        Ok(visited.len().into())
     }
 }
 
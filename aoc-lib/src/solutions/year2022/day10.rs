@@ -16,20 +16,20 @@ impl Solution for Day10 {
 
     fn part1(&mut self, ctx: &Context) -> SolutionResult {
         let (signal_strength, _) = execute_program(ctx)?;
-        Ok(signal_strength.to_string())
+        Ok(signal_strength.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
         let (_, screen) = execute_program(ctx)?;
-        let screen_str = screen
+        let lines = screen
             .chunks(WIDTH as usize)
             .map(|x| x.iter().join(""))
-            .join("\n");
-        let letters = ocr::parse_string_to_letters(&screen_str);
+            .collect_vec();
+        let letters = ocr::parse_string_to_letters(&lines.join("\n"));
 
         match letters.len() {
-            8 => Ok(letters),
-            _ => Ok(screen_str),
+            8 => Ok(Answer::grid(lines, Some(letters))),
+            _ => Ok(Answer::grid(lines, None)),
         }
     }
 }
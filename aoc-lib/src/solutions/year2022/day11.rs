@@ -13,14 +13,14 @@ impl Solution for Day11 {
         const DIVIDE_WORRY: i64 = 3;
         const ROUNDS: i64 = 20;
         let monkey_business_level = calc_monkey_business(ctx, DIVIDE_WORRY, ROUNDS)?;
-        Ok(monkey_business_level.to_string())
+        Ok(monkey_business_level.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
         const DIVIDE_WORRY: i64 = 1;
         const ROUNDS: i64 = 10000;
         let monkey_business_level = calc_monkey_business(ctx, DIVIDE_WORRY, ROUNDS)?;
-        Ok(monkey_business_level.to_string())
+        Ok(monkey_business_level.into())
     }
 }
 
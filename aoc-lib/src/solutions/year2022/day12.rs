@@ -16,7 +16,7 @@ impl Solution for Day12 {
         let fewest_steps = step_map[map.start.y as usize][map.start.x as usize]
             .ok_or("no path available from start")?;
 
-        Ok(fewest_steps.to_string())
+        Ok(fewest_steps.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -24,7 +24,7 @@ impl Solution for Day12 {
         let (_, shortest_path) = find_paths(&map);
         let shortest_path = shortest_path.ok_or("no path available from any elevation 'a'")?;
 
-        Ok(shortest_path.to_string())
+        Ok(shortest_path.into())
     }
 }
 
@@ -17,7 +17,7 @@ impl Solution for Day13 {
             }
         }
 
-        Ok(sum.to_string())
+        Ok(sum.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -30,7 +30,7 @@ impl Solution for Day13 {
             .positions(|x| dividers.contains(&x))
             .fold(1, |a, x| a * (x + 1));
 
-        Ok(key.to_string())
+        Ok(key.into())
     }
 }
 
@@ -15,30 +15,40 @@ static SAND_DIRECTIONS: [Point; 3] = [
 
 #[derive(Default)]
 pub struct Day14;
-impl Solution for Day14 {
+impl ParsedSolution for Day14 {
+    type Parsed = Cave;
+
     fn info(&self) -> SolutionInfo {
         Title::new(2022, 14, "Regolith Reservoir")
     }
 
-    fn part1(&mut self, ctx: &Context) -> SolutionResult {
-        let mut cave = parse_cave(ctx)?;
+    fn parse(&mut self, ctx: &Context) -> GenericResult<Self::Parsed> {
+        parse_cave(ctx)
+    }
+
+    fn part1(&mut self, cave: &Self::Parsed, ctx: &Context) -> SolutionResult {
+        let mut cave = cave.clone();
         let mut count = 0;
         while fall_sand(&SAND_START, &mut cave, false) {
+            ctx.frame(|| cave.lines());
             count += 1;
         }
+        ctx.final_frame(|| cave.lines());
 
-        Ok(count.to_string())
+        Ok(count.into())
     }
 
-    fn part2(&mut self, ctx: &Context) -> SolutionResult {
-        let mut cave = parse_cave(ctx)?;
+    fn part2(&mut self, cave: &Self::Parsed, ctx: &Context) -> SolutionResult {
+        let mut cave = cave.clone();
         let mut count = 0;
         while fall_sand(&SAND_START, &mut cave, true) {
             ctx.progress((cave.floor - cave.top_left.y) as f32 / cave.floor as f32);
+            ctx.frame(|| cave.lines());
             count += 1;
         }
+        ctx.final_frame(|| cave.lines());
 
-        Ok(count.to_string())
+        Ok(count.into())
     }
 }
 
@@ -102,27 +112,33 @@ fn parse_cave(ctx: &Context) -> GenericResult<Cave> {
 }
 
 #[allow(dead_code)]
-fn print_cave(cave: &Cave) {
-    println!("{:?}..{:?}", cave.top_left, cave.bottom_right);
-    for y in cave.top_left.y..=cave.bottom_right.y {
-        for x in cave.top_left.x..=cave.bottom_right.x {
-            match cave.map.get(&Point::new(x, y)) {
-                Some(c) => print!("{}", c),
-                None => print!("{}", TILE_AIR),
-            }
-        }
-        println!("");
+fn log_cave(cave: &Cave, ctx: &Context) {
+    if !ctx.log_enabled(LogLevel::Debug) {
+        return;
     }
+    let header = format!("{:?}..{:?}", cave.top_left, cave.bottom_right);
+    let rows = cave.lines();
+    ctx.log(LogLevel::Debug, [header].into_iter().chain(rows).join("\n"));
 }
 
-#[derive(Debug, Default)]
-struct Cave {
+#[derive(Debug, Default, Clone)]
+pub struct Cave {
     map: HashMap<Point, char>,
     top_left: Point,
     bottom_right: Point,
     floor: i32,
 }
 impl Cave {
+    fn lines(&self) -> Vec<String> {
+        (self.top_left.y..=self.bottom_right.y)
+            .map(|y| {
+                (self.top_left.x..=self.bottom_right.x)
+                    .map(|x| *self.map.get(&Point::new(x, y)).unwrap_or(&TILE_AIR))
+                    .collect::<String>()
+            })
+            .collect_vec()
+    }
+
     fn insert(&mut self, point: Point, tile: char) -> Option<char> {
         self.top_left.x = self.top_left.x.min(point.x);
         self.top_left.y = self.top_left.y.min(point.y);
@@ -38,7 +38,7 @@ impl Solution for Day15 {
             .count() as i64;
         let position_count = covered_area - beacons_in_area;
 
-        Ok(position_count.to_string())
+        Ok(position_count.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -68,7 +68,7 @@ impl Solution for Day15 {
             .ok_or("could not find beacon")?;
         let tuning_frequency = beacon_pos.x * 4000000 + beacon_pos.y;
 
-        Ok(tuning_frequency.to_string())
+        Ok(tuning_frequency.into())
     }
 }
 
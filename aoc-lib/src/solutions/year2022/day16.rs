@@ -19,7 +19,7 @@ impl Solution for Day16 {
             .next()
             .ok_or("no path found")?;
 
-        Ok(best_pressure.to_string())
+        Ok((*best_pressure).into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -27,7 +27,7 @@ impl Solution for Day16 {
         let routes = find_routes_for(26, &network);
         let best_pressure = find_best_route_pair_pressure(routes, ctx);
 
-        Ok(best_pressure.to_string())
+        Ok(best_pressure.into())
     }
 }
 
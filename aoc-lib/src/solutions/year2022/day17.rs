@@ -10,6 +10,8 @@ use std::{collections::HashMap, ops::RangeInclusive, str::FromStr};
 static CAVE_WIDTH: i64 = 7;
 static TILE_AIR: char = '.';
 static TILE_ROCK: char = '#';
+static TILE_FALLING_ROCK: char = '@';
+static FRAME_HEIGHT: i64 = 24;
 static ROCK_KINDS: Lazy<Vec<RockShape>> = Lazy::new(|| {
     [
         "####",
@@ -33,17 +35,17 @@ impl Solution for Day17 {
     fn part1(&mut self, ctx: &Context) -> SolutionResult {
         let mut cave = parse_cave(ctx)?;
         drop_rocks(ctx, &mut cave, 2022);
-        Ok(cave.height().to_string())
+        Ok(cave.height().into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
         let mut cave = parse_cave(ctx)?;
         drop_rocks(ctx, &mut cave, 1000000000000);
-        Ok(cave.height().to_string())
+        Ok(cave.height().into())
     }
 }
 
-fn drop_rocks(_ctx: &Context, cave: &mut Cave, target_drop_count: i64) {
+fn drop_rocks(ctx: &Context, cave: &mut Cave, target_drop_count: i64) {
     let top_count =
         ROCK_KINDS.len() as i64 * ROCK_KINDS.iter().map(|x| x.height).max().unwrap_or(0);
 
@@ -55,6 +57,8 @@ fn drop_rocks(_ctx: &Context, cave: &mut Cave, target_drop_count: i64) {
     let mut rock = drop_rock(cave, rock_idx);
 
     while drop_count < target_drop_count {
+        ctx.frame(|| cave_frame(cave, &rock));
+
         // handle wind
         rock.pos.x += cave.wind[wind_idx];
         if cave.intersecting(&rock) {
@@ -93,6 +97,7 @@ fn drop_rocks(_ctx: &Context, cave: &mut Cave, target_drop_count: i64) {
         rock_idx = (rock_idx + 1) % ROCK_KINDS.len();
         rock = drop_rock(cave, rock_idx);
     }
+    ctx.final_frame(|| cave_frame(cave, &rock));
 }
 
 /// Create a new rock of the given kind at the correct height.
@@ -126,16 +131,33 @@ fn cave_top(cave: &Cave, top_row_count: i64) -> String {
     .collect::<String>()
 }
 
-/// Print the cave to the standard output.
+/// Draw the top of the cave with the falling rock.
+fn cave_frame(cave: &Cave, rock: &RockShape) -> Vec<String> {
+    let rock_tiles = rock.tiles.iter().map(|p| *p + rock.pos).collect_vec();
+    (rock.pos.y..rock.pos.y + FRAME_HEIGHT)
+        .map(|y| {
+            let row = (cave.top_left.x..=cave.bottom_right.x)
+                .map(|x| match cave.tiles.get(&Point::new(x, y)) {
+                    Some(c) => *c,
+                    None if rock_tiles.contains(&Point::new(x, y)) => TILE_FALLING_ROCK,
+                    None => TILE_AIR,
+                })
+                .collect::<String>();
+            format!("|{}|", row)
+        })
+        .collect_vec()
+}
+
+/// Log the cave as a debug message.
 #[allow(dead_code)]
-fn print_cave(cave: &Cave) {
+fn log_cave(cave: &Cave, ctx: &Context) {
     let cave_str = cave_top(cave, (cave.top_left.y - cave.bottom_right.y).abs())
         .chars()
         .chunks(CAVE_WIDTH as usize)
         .into_iter()
         .map(|x| x.collect::<String>())
         .join("\n");
-    println!("{}", cave_str);
+    ctx.log(LogLevel::Debug, cave_str);
 }
 
 fn parse_cave(ctx: &Context) -> GenericResult<Cave> {
@@ -22,13 +22,13 @@ impl Solution for Day18 {
     fn part1(&mut self, ctx: &Context) -> SolutionResult {
         let voxels = parse_voxels(ctx)?;
         let surface_area = total_surface_area(&voxels);
-        Ok(surface_area.to_string())
+        Ok(surface_area.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
         let voxels = parse_voxels(ctx)?;
         let surface_area = exterior_surface_area(&voxels);
-        Ok(surface_area.to_string())
+        Ok(surface_area.into())
     }
 }
 
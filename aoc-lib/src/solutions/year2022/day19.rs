@@ -6,7 +6,7 @@ use crate::{
 use itertools::Itertools;
 use rayon::prelude::*;
 use regex::Regex;
-use std::{collections::HashMap, hash::Hash, sync::Mutex};
+use std::{collections::HashMap, hash::Hash};
 
 static GEO_IDX: usize = 3;
 
@@ -29,7 +29,7 @@ impl Solution for Day19 {
             .collect_vec();
 
         let quality_level_sum = results.iter().fold(0, |a, (id, geo)| a + id * geo);
-        Ok(quality_level_sum.to_string())
+        Ok(quality_level_sum.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -44,24 +44,19 @@ impl Solution for Day19 {
                 })
                 .collect_vec()
         } else {
-            let progress = Mutex::new(0);
+            let progress = ctx.progress_scope(blueprints.len());
             blueprints
                 .par_iter()
                 .map(|bp| {
                     let result = find_best(bp, 32);
-                    {
-                        let mut progress = progress.lock().unwrap();
-                        *progress += 1;
-                        ctx.progress(*progress as f32 / blueprints.len() as f32);
-                    }
-
+                    progress.tick();
                     result
                 })
                 .collect::<Vec<_>>()
         };
 
         let product = results.iter().fold(1, |a, x| a * x);
-        Ok(product.to_string())
+        Ok(product.into())
     }
 }
 
@@ -13,7 +13,7 @@ impl Solution for Day20 {
         mix(&numbers, &mut ring);
         let sum = grove_coordinate_sum(&numbers, &ring)?;
 
-        Ok(sum.to_string())
+        Ok(sum.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -24,7 +24,7 @@ impl Solution for Day20 {
         }
         let sum = grove_coordinate_sum(&numbers, &ring)?;
 
-        Ok(sum.to_string())
+        Ok(sum.into())
     }
 }
 
@@ -110,14 +110,15 @@ impl RingItem {
 }
 
 #[allow(dead_code)]
-fn print_ring(ring: &Vec<RingItem>) {
+fn log_ring(ring: &Vec<RingItem>, ctx: &Context) {
+    let mut values = Vec::new();
     let mut current = 0;
     loop {
-        print!("{}, ", ring[current].value);
+        values.push(ring[current].value);
         current = ring[current].step(1, ring);
         if current == 0 {
             break;
         }
     }
-    println!();
+    ctx.log(LogLevel::Debug, values.iter().join(", "));
 }
@@ -16,7 +16,7 @@ impl Solution for Day21 {
     fn part1(&mut self, ctx: &Context) -> SolutionResult {
         let monkeys = parse_input(ctx)?;
         let root = calc_monkey(ROOT, &monkeys, &mut HashMap::new(), false)?;
-        Ok(root.to_string())
+        Ok(root.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -77,7 +77,7 @@ impl Solution for Day21 {
             left = b;
         }
 
-        Ok(right.to_string())
+        Ok(right.into())
     }
 }
 
@@ -29,30 +29,35 @@ static DIRECTIONS3: [Point3; 4] = [
 
 #[derive(Default)]
 pub struct Day22;
-impl Solution for Day22 {
+impl ParsedSolution for Day22 {
+    type Parsed = Map;
+
     fn info(&self) -> SolutionInfo {
         Title::new(2022, 22, "Monkey Map")
     }
 
-    fn part1(&mut self, ctx: &Context) -> SolutionResult {
-        let map = parse_input(ctx, WrapMode::Flat)?;
-        let (pos, facing) = walk(&map)?;
+    fn parse(&mut self, ctx: &Context) -> GenericResult<Self::Parsed> {
+        parse_input(ctx)
+    }
+
+    fn part1(&mut self, map: &Self::Parsed, _ctx: &Context) -> SolutionResult {
+        let (pos, facing) = walk(map)?;
         let password = (pos.y + 1) * 1000 + (pos.x + 1) * 4 + facing as i32;
 
-        Ok(password.to_string())
+        Ok(password.into())
     }
 
-    fn part2(&mut self, ctx: &Context) -> SolutionResult {
-        let map = parse_input(ctx, WrapMode::Cube)?;
-        let (pos, facing) = walk_cube(&map, ctx)?;
+    fn part2(&mut self, map: &Self::Parsed, ctx: &Context) -> SolutionResult {
+        let (pos, facing) = walk_cube(map, ctx)?;
         let password = (pos.y + 1) * 1000 + (pos.x + 1) * 4 + facing as i32;
 
-        Ok(password.to_string())
+        Ok(password.into())
     }
 }
 
 fn walk_cube(map: &Map, ctx: &Context) -> GenericResult<(Point, usize)> {
-    let mut cube = map.cube.clone();
+    // Folded here, as only part 2 needs the cube
+    let mut cube = create_cube(map)?;
     let mut pos3 = cube
         .get_3d_pos(&map.start.clone())
         .ok_or("invalid 3d map")?;
@@ -145,31 +150,26 @@ fn get_next_pos(start: Point, facing: usize, map: &Map) -> GenericResult<(Point,
 }
 
 #[allow(dead_code)]
-fn print_map(map: &Map, visited: &HashMap<Point, usize>) {
-    println!("\nmap:");
+fn log_map(map: &Map, visited: &HashMap<Point, usize>, ctx: &Context) {
+    let mut map_str = String::from("map:");
     for y in 0..map.height {
+        map_str.push('\n');
         for x in 0..map.width {
             let p = Point::new(x, y);
-            if let Some(facing) = visited.get(&p) {
-                match facing {
-                    0 => print!(">"),
-                    1 => print!("v"),
-                    2 => print!("<"),
-                    3 => print!("^"),
-                    _ => print!("?"),
-                }
-            } else {
-                match map.tiles.get(&p) {
-                    Some(c) => print!("{}", c),
-                    None => print!(" "),
-                }
-            }
+            map_str.push(match visited.get(&p) {
+                Some(0) => '>',
+                Some(1) => 'v',
+                Some(2) => '<',
+                Some(3) => '^',
+                Some(_) => '?',
+                None => *map.tiles.get(&p).unwrap_or(&' '),
+            });
         }
-        println!();
     }
+    ctx.log(LogLevel::Debug, map_str);
 }
 
-fn parse_input(ctx: &Context, mode: WrapMode) -> GenericResult<Map> {
+fn parse_input(ctx: &Context) -> GenericResult<Map> {
     let input = ctx.input();
     let (input_map, input_path) = input.split_once("\n\n").ok_or("invalid input")?;
 
@@ -232,10 +232,7 @@ fn parse_input(ctx: &Context, mode: WrapMode) -> GenericResult<Map> {
         side_len,
         ..Default::default()
     };
-    match mode {
-        WrapMode::Flat => add_flat_portals(&mut map, &ranges_x, &ranges_y),
-        WrapMode::Cube => add_cube(&mut map)?,
-    };
+    add_flat_portals(&mut map, &ranges_x, &ranges_y);
 
     Ok(map)
 }
@@ -257,7 +254,11 @@ fn add_flat_portals(
     }
 }
 
-fn add_cube(map: &mut Map) -> GenericResult {
+fn create_cube(map: &Map) -> GenericResult<Cube> {
+    if map.side_len == 0 {
+        Err("map is too small to fold into a cube")?;
+    }
+
     // find position of sides within the tile map
     let mut sides = Vec::new();
     for y in 0..(map.height / map.side_len) {
@@ -270,16 +271,15 @@ fn add_cube(map: &mut Map) -> GenericResult {
     }
 
     // map sides to cube
-    let start = sides[0].clone();
+    let start = *sides.first().ok_or("invalid 3d map")?;
     let sides_map: HashSet<Point> = HashSet::from_iter(sides);
     let mut cube = Cube {
         side_len: map.side_len,
         ..Default::default()
     };
-    fill_cube(&start, &sides_map, &mut HashSet::new(), &mut cube, &map);
+    fill_cube(&start, &sides_map, &mut HashSet::new(), &mut cube, map);
 
-    map.cube = cube;
-    Ok(())
+    Ok(cube)
 }
 
 fn fill_cube(
@@ -318,9 +318,8 @@ fn fill_cube(
 }
 
 #[derive(Debug, Default)]
-struct Map {
+pub struct Map {
     start: Point,
-    cube: Cube,
     tiles: HashMap<Point, char>,
     width: i32,
     height: i32,
@@ -382,16 +381,22 @@ impl Cube {
     }
 
     #[allow(dead_code)]
-    fn print_coords(&self, map: &Map, tile: char) {
+    fn log_coords(&self, map: &Map, tile: char, ctx: &Context) {
         let coords = self
             .tiles
             .iter()
             .filter(|(_, p2)| *map.tiles.get(&p2).unwrap_or(&TILE_OPEN) == tile)
             .map(|(p1, _)| p1)
             .collect_vec();
-        println!("x=[{}]", coords.iter().map(|p| p.x).join(", "));
-        println!("y=[{}]", coords.iter().map(|p| p.y).join(", "));
-        println!("z=[{}]", coords.iter().map(|p| p.z).join(", "));
+        ctx.log(
+            LogLevel::Debug,
+            format!(
+                "x=[{}]\ny=[{}]\nz=[{}]",
+                coords.iter().map(|p| p.x).join(", "),
+                coords.iter().map(|p| p.y).join(", "),
+                coords.iter().map(|p| p.z).join(", ")
+            ),
+        );
     }
 }
 
@@ -399,11 +404,6 @@ fn rad(deg: i32) -> f32 {
     (deg as f32).to_radians()
 }
 
-enum WrapMode {
-    Flat,
-    Cube,
-}
-
 #[derive(
     Copy, Clone, Debug, Default, Hash, PartialEq, Eq, Constructor, Add, Sub, AddAssign, SubAssign,
 )]
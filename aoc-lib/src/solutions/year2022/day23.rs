@@ -32,15 +32,17 @@ impl Solution for Day23 {
     fn part1(&mut self, ctx: &Context) -> SolutionResult {
         let mut map = parse_map(ctx);
         for round in 0..10 {
+            ctx.frame(|| map.lines());
             (map, ..) = execute_round(map, round);
         }
+        ctx.final_frame(|| map.lines());
 
         let (min, max) = map.bounds();
         let empty_count = itertools::iproduct!(min.x..=max.x, min.y..=max.y)
             .filter(|(x, y)| !map.tiles.contains(&Point::new(*x, *y)))
             .count();
 
-        Ok(empty_count.to_string())
+        Ok(empty_count.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -49,11 +51,13 @@ impl Solution for Day23 {
         let mut round = 0;
         while move_count > 0 {
             ctx.progress(round as f32 / 1000.0);
+            ctx.frame(|| map.lines());
             (map, move_count) = execute_round(map, round);
             round += 1;
         }
+        ctx.final_frame(|| map.lines());
 
-        Ok(round.to_string())
+        Ok(round.into())
     }
 }
 
@@ -132,18 +136,23 @@ impl Map {
         (min, max)
     }
 
-    #[allow(dead_code)]
-    fn print(&self) {
+    fn lines(&self) -> Vec<String> {
         let (min, max) = self.bounds();
-        for y in min.y..=max.y {
-            for x in min.x..=max.x {
-                match self.tiles.contains(&Point::new(x, y)) {
-                    true => print!("{TILE_ELF}"),
-                    false => print!("{TILE_EMPTY}"),
-                }
-            }
-            println!();
-        }
+        (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| match self.tiles.contains(&Point::new(x, y)) {
+                        true => TILE_ELF,
+                        false => TILE_EMPTY,
+                    })
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    fn log(&self, ctx: &Context) {
+        ctx.log(LogLevel::Debug, self.lines().join("\n"));
     }
 }
 
@@ -7,6 +7,7 @@ use std::{collections::HashMap, hash::Hash};
 const TILE_WALL: char = '#';
 const TILE_EMPTY: char = '.';
 const TILE_BLIZZARDS: [char; 4] = ['>', 'v', '<', '^'];
+const TILE_EXPEDITION: char = 'E';
 const DIRECTIONS: [Point; 4] = [
     Point { x: 1, y: 0 },  // East
     Point { x: 0, y: 1 },  // South
@@ -30,9 +31,9 @@ impl Solution for Day24 {
 
     fn part1(&mut self, ctx: &Context) -> SolutionResult {
         let map = parse_map(ctx)?;
-        let time = traverse(&map, &map.start, &map.goal, 0, &mut HashMap::new())
+        let time = traverse(&map, &map.start, &map.goal, 0, &mut HashMap::new(), ctx)
             .ok_or("could not find path")?;
-        Ok(time.to_string())
+        Ok(time.into())
     }
 
     fn part2(&mut self, ctx: &Context) -> SolutionResult {
@@ -41,13 +42,15 @@ impl Solution for Day24 {
         let err = "could not find path";
         let mut blizzards_at = HashMap::new();
         ctx.progress(0.01);
-        let time = traverse(&map, &map.start, &map.goal, 0, &mut blizzards_at).ok_or(err)?;
+        let time = traverse(&map, &map.start, &map.goal, 0, &mut blizzards_at, ctx).ok_or(err)?;
         ctx.progress(0.3333);
-        let time = traverse(&map, &map.goal, &map.start, time, &mut blizzards_at).ok_or(err)?;
+        let time =
+            traverse(&map, &map.goal, &map.start, time, &mut blizzards_at, ctx).ok_or(err)?;
         ctx.progress(0.6666);
-        let time = traverse(&map, &map.start, &map.goal, time, &mut blizzards_at).ok_or(err)?;
+        let time =
+            traverse(&map, &map.start, &map.goal, time, &mut blizzards_at, ctx).ok_or(err)?;
 
-        Ok(time.to_string())
+        Ok(time.into())
     }
 }
 
@@ -57,6 +60,7 @@ fn traverse(
     goal: &Point,
     elapsed: i32,
     blizzards_at: &mut HashMap<i32, HashMap<Point, Vec<usize>>>,
+    ctx: &Context,
 ) -> Option<i32> {
     blizzards_at.insert(0, map.starting_blizzards.clone());
     let mut queue = PriorityQueue::new();
@@ -64,8 +68,10 @@ fn traverse(
 
     while let Some(((time, pos), _priority)) = queue.pop() {
         if pos == *goal {
+            ctx.final_frame(|| map.lines(&blizzards_at[&time], Some(pos)));
             return Some(time);
         }
+        ctx.frame(|| map.lines(&blizzards_at[&time], Some(pos)));
 
         let next_blizzards = match blizzards_at.get(&(time + 1)) {
             Some(blizzards) => blizzards,
@@ -163,25 +169,36 @@ struct Map {
     starting_blizzards: HashMap<Point, Vec<usize>>,
 }
 impl Map {
-    #[allow(dead_code)]
-    fn print(&self) {
-        println!();
+    /// Draw the map with the given blizzards and optionally the expedition.
+    fn lines(
+        &self,
+        blizzards: &HashMap<Point, Vec<usize>>,
+        expedition: Option<Point>,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
         for y in -1..self.height as i32 + 1 {
+            let mut line = String::new();
             for x in -1..self.width as i32 + 1 {
                 let p = Point::new(x, y);
-                match self.starting_blizzards.get(&p) {
+                match blizzards.get(&p) {
+                    _ if expedition == Some(p) => line.push(TILE_EXPEDITION),
                     Some(blizzards) => match blizzards.len() {
-                        1 => print!("{}", TILE_BLIZZARDS[blizzards[0]]),
-                        _ => print!("{}", blizzards.len().min(9)),
-                    },
-                    None => match self.tiles.get(&p) {
-                        Some(c) => print!("{}", c),
-                        None => print!(" "),
+                        1 => line.push(TILE_BLIZZARDS[blizzards[0]]),
+                        n => line.push_str(&n.min(9).to_string()),
                     },
+                    None => line.push(*self.tiles.get(&p).unwrap_or(&' ')),
                 }
             }
-            println!();
+            lines.push(line);
         }
+
+        lines
+    }
+
+    #[allow(dead_code)]
+    fn log(&self, ctx: &Context) {
+        let lines = self.lines(&self.starting_blizzards, None);
+        ctx.log(LogLevel::Debug, lines.join("\n"));
     }
 }
 
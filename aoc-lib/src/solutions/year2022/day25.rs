@@ -16,11 +16,11 @@ impl Solution for Day25 {
             .reduce(|a, x| a + x)
             .ok_or("empty input")?;
 
-        Ok(sum.to_string())
+        Ok(sum.to_string().into())
     }
 
     fn part2(&mut self, _ctx: &Context) -> SolutionResult {
-        Ok("*".to_owned())
+        Ok("*".into())
     }
 }
 
@@ -0,0 +1,87 @@
+use crate::answers::{self, KnownAnswers, Verdict};
+use crate::inputs::DEFAULT_PROFILE;
+use crate::solution::Answer;
+use crate::util::YearDay;
+use std::{env, process};
+
+#[test]
+fn check_compares_with_the_known_answer() {
+    let answer = Answer::Integer(42);
+    assert_eq!(answers::check(Some("42"), &answer), Verdict::Correct);
+    assert_eq!(
+        answers::check(Some("43"), &answer),
+        Verdict::Wrong("43".to_owned())
+    );
+    assert_eq!(answers::check(None, &answer), Verdict::Unknown);
+}
+
+#[test]
+fn check_ignores_surrounding_whitespace() {
+    let answer = Answer::Text("CMZ\n".to_owned());
+    assert_eq!(answers::check(Some(" CMZ \n"), &answer), Verdict::Correct);
+    assert_eq!(
+        answers::check(Some("C MZ"), &answer),
+        Verdict::Wrong("C MZ".to_owned())
+    );
+}
+
+#[test]
+fn check_compares_grids_by_their_text() {
+    let lines = vec!["#..#".to_owned(), "####".to_owned()];
+    let recognized = Answer::grid(lines.clone(), Some("H".to_owned()));
+    assert_eq!(answers::check(Some("H"), &recognized), Verdict::Correct);
+
+    let unrecognized = Answer::grid(lines, None);
+    assert_eq!(
+        answers::check(Some("#..#\n####\n"), &unrecognized),
+        Verdict::Correct
+    );
+}
+
+#[test]
+fn known_answers_are_keyed_by_day_and_profile() {
+    let json =
+        r#"{ "2022/05": { "1": "CMZ", "2": "MCD" }, "2022/05/alice": { "1": "QNHWJVJZW" } }"#;
+    let answers: KnownAnswers = serde_json::from_str(json).unwrap();
+    let day = YearDay::new(2022, 5);
+
+    assert_eq!(answers.get(&day, DEFAULT_PROFILE, 1), Some("CMZ"));
+    assert_eq!(answers.get(&day, DEFAULT_PROFILE, 2), Some("MCD"));
+    assert_eq!(answers.get(&day, "alice", 1), Some("QNHWJVJZW"));
+    // The answers of a profile do not fall back to the default profile
+    assert_eq!(answers.get(&day, "alice", 2), None);
+    assert_eq!(answers.get(&day, "bob", 1), None);
+    assert_eq!(
+        answers.get(&YearDay::new(2022, 6), DEFAULT_PROFILE, 1),
+        None
+    );
+    assert_eq!(
+        answers.check(&day, "alice", 1, &Answer::Text("QNHWJVJZW".to_owned())),
+        Verdict::Correct
+    );
+}
+
+#[test]
+fn inserted_answers_are_saved_with_day_and_profile_keys() {
+    let mut answers = KnownAnswers::default();
+    let day = YearDay::new(2022, 5);
+    answers.insert(&day, DEFAULT_PROFILE, 1, "CMZ".to_owned());
+    answers.insert(&day, "alice", 1, "QNHWJVJZW".to_owned());
+
+    let json = serde_json::to_value(&answers).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "2022/05": { "1": "CMZ" }, "2022/05/alice": { "1": "QNHWJVJZW" } })
+    );
+    assert_eq!(answers.parts(&day, "alice").get(&1).unwrap(), "QNHWJVJZW");
+}
+
+#[test]
+fn load_returns_no_answers_without_a_file() {
+    let path = env::temp_dir().join(format!("aoc-missing-answers-{}.json", process::id()));
+    let answers = KnownAnswers::load(&path).unwrap();
+    assert_eq!(
+        answers.get(&YearDay::new(2022, 5), DEFAULT_PROFILE, 1),
+        None
+    );
+}
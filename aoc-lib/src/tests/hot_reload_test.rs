@@ -0,0 +1,33 @@
+use crate::core::hot_reload::{self, aoc_interface_hash};
+use std::ffi::{CStr, CString};
+
+#[test]
+fn interface_hash_of_the_same_build_is_accepted() {
+    // SAFETY: the exported hash is a static null-terminated string
+    let own_hash = unsafe { CStr::from_ptr(aoc_interface_hash()) };
+
+    assert!(hot_reload::check_interface_hash(own_hash).is_ok());
+}
+
+#[test]
+fn library_with_a_different_interface_hash_is_refused() {
+    // SAFETY: the exported hash is a static null-terminated string
+    let own_hash = unsafe { CStr::from_ptr(aoc_interface_hash()) }
+        .to_str()
+        .unwrap();
+    let other_hashes = [
+        CString::new(format!("{}0", own_hash)).unwrap(),
+        CString::new("").unwrap(),
+        CString::new(vec![0xff]).unwrap(),
+    ];
+
+    for hash in &other_hashes {
+        let err = hot_reload::check_interface_hash(hash).unwrap_err();
+        assert!(
+            err.to_string().contains("rebuild both"),
+            "unexpected error for {:?}: {}",
+            hash,
+            err
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use crate::inputs::{self, DirectoryInputs, InputProvider, DEFAULT_PROFILE};
+use crate::util::YearDay;
+use include_dir::{Dir, DirEntry, File};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::{env, fs, process};
+
+/// Directory of input files removed when dropped.
+struct TempInputs {
+    path: PathBuf,
+}
+impl TempInputs {
+    /// Creates the given files, with the name of the directory and their paths as their contents.
+    fn new(name: &str, files: &[&str]) -> TempInputs {
+        let path = env::temp_dir().join(format!("aoc-inputs-{}-{}", process::id(), name));
+        _ = fs::remove_dir_all(&path);
+        for file in files {
+            let file_path = path.join(file);
+            fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+            fs::write(&file_path, format!("{}:{}", name, file)).unwrap();
+        }
+        TempInputs { path }
+    }
+
+    fn provider(&self) -> Arc<dyn InputProvider> {
+        Arc::new(DirectoryInputs {
+            path: self.path.clone(),
+        })
+    }
+}
+impl Drop for TempInputs {
+    fn drop(&mut self) {
+        _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn day(day: u32) -> YearDay {
+    YearDay::new(2000, day)
+}
+
+fn profiles(provider: &dyn InputProvider, year_day: &YearDay) -> Vec<String> {
+    let mut profiles = provider.profiles(year_day).unwrap();
+    profiles.sort();
+    profiles
+}
+
+#[test]
+fn directory_inputs_read_the_files_of_the_profiles() {
+    let dir = TempInputs::new(
+        "read",
+        &[
+            "year2000/day05.txt",
+            "year2000/day05/alice.txt",
+            "year2000/day05/notes.md",
+            "year2000/day06/bob.txt",
+        ],
+    );
+    let provider = dir.provider();
+
+    let input = provider.get(&day(5), DEFAULT_PROFILE).unwrap();
+    assert_eq!(input.as_deref(), Some("read:year2000/day05.txt"));
+    let input = provider.get(&day(5), "alice").unwrap();
+    assert_eq!(input.as_deref(), Some("read:year2000/day05/alice.txt"));
+    assert_eq!(provider.get(&day(5), "bob").unwrap(), None);
+    assert_eq!(provider.get(&day(6), DEFAULT_PROFILE).unwrap(), None);
+
+    assert_eq!(profiles(provider.as_ref(), &day(5)), ["alice", "default"]);
+    assert_eq!(profiles(provider.as_ref(), &day(6)), ["bob"]);
+    assert!(profiles(provider.as_ref(), &day(7)).is_empty());
+    assert!(profiles(provider.as_ref(), &YearDay::new(1999, 5)).is_empty());
+}
+
+#[test]
+fn resolve_takes_the_input_from_the_first_provider_having_it() {
+    let first = TempInputs::new("resolve-first", &["year2000/day05.txt"]);
+    let second = TempInputs::new(
+        "resolve-second",
+        &["year2000/day05.txt", "year2000/day05/alice.txt"],
+    );
+    let providers = [first.provider(), second.provider()];
+
+    let input = inputs::resolve(&providers, &day(5), DEFAULT_PROFILE).unwrap();
+    assert_eq!(input, "resolve-first:year2000/day05.txt");
+    let input = inputs::resolve(&providers, &day(5), "alice").unwrap();
+    assert_eq!(input, "resolve-second:year2000/day05/alice.txt");
+}
+
+#[test]
+fn resolve_reports_the_missing_profile_and_the_providers() {
+    let dir = TempInputs::new("missing", &["year2000/day05.txt"]);
+    let providers = [dir.provider()];
+
+    let err = inputs::resolve(&providers, &day(5), "carol").unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.contains("2000 day 5 profile 'carol'"),
+        "{}",
+        message
+    );
+    assert!(
+        message.contains(&dir.provider().description()),
+        "{}",
+        message
+    );
+
+    let err = inputs::resolve(&providers, &day(6), DEFAULT_PROFILE).unwrap_err();
+    assert!(!err.to_string().contains("profile"), "{}", err);
+}
+
+#[test]
+fn resolve_profiles_lists_each_profile_once_with_the_default_first() {
+    let first = TempInputs::new(
+        "profiles-first",
+        &["year2000/day05/bob.txt", "year2000/day05/alice.txt"],
+    );
+    let second = TempInputs::new(
+        "profiles-second",
+        &["year2000/day05/alice.txt", "year2000/day05.txt"],
+    );
+    let providers = [first.provider(), second.provider()];
+
+    let profiles = inputs::resolve_profiles(&providers, &day(5)).unwrap();
+    assert_eq!(profiles, ["default", "alice", "bob"]);
+    assert!(inputs::resolve_profiles(&providers, &day(6))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn input_map_skips_unrelated_files() {
+    static DAY_DIR: [DirEntry; 2] = [
+        DirEntry::File(File::new("year2000/day02/big.txt", b"big")),
+        DirEntry::File(File::new("year2000/day02/.DS_Store", b"")),
+    ];
+    static YEAR_DIR: [DirEntry; 5] = [
+        DirEntry::File(File::new("year2000/day01.txt", b"default")),
+        DirEntry::File(File::new("year2000/notes.txt", b"notes")),
+        DirEntry::File(File::new("year2000/day01.txt.bak", b"backup")),
+        DirEntry::Dir(Dir::new("year2000/day02", &DAY_DIR)),
+        DirEntry::Dir(Dir::new("year2000/drafts", &[])),
+    ];
+    static INPUT_DIR: [DirEntry; 3] = [
+        DirEntry::Dir(Dir::new("year2000", &YEAR_DIR)),
+        DirEntry::Dir(Dir::new("scratch", &[])),
+        DirEntry::File(File::new(".DS_Store", b"")),
+    ];
+
+    let map = inputs::create_input_map(&Dir::new("", &INPUT_DIR));
+    let mut entries = map
+        .iter()
+        .map(|((yd, profile), input)| (yd.day, profile.as_str(), input.as_str()))
+        .collect::<Vec<_>>();
+    entries.sort();
+    assert_eq!(
+        entries,
+        vec![(1, DEFAULT_PROFILE, "default"), (2, "big", "big")]
+    );
+}
@@ -3,4 +3,10 @@
 // Module definitions
 pub mod year2021;
 pub mod year2022;
+pub mod answers_test;
+pub mod hot_reload_test;
+pub mod inputs_test;
+pub mod recording_test;
+pub mod solution_runner_test;
+pub mod solution_test;
 pub mod util;
@@ -0,0 +1,100 @@
+use crate::answers::Verdict;
+use crate::core::recording::{self, ReplaySolutionRunner, TeeSyncStream};
+use crate::core::solution_runner::*;
+use crate::solution::{Answer, LogLevel};
+use crate::util::YearDay;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::{env, fs, process};
+
+fn pack<T>(part: Option<u8>, value: T) -> ResultPack<T> {
+    ResultPack {
+        year_day: YearDay::new(2000, 1),
+        part,
+        value,
+        duration: Duration::from_micros(1234),
+        verdict: None,
+    }
+}
+
+/// One event of each kind, as sent by a run.
+fn events() -> Vec<SolveProgress> {
+    vec![
+        SolveProgress::Start(YearDay::new(2000, 1), "Test Day".to_owned()),
+        SolveProgress::Initialized(pack(None, ())),
+        SolveProgress::Progress(pack(Some(1), 0.25)),
+        SolveProgress::Log(pack(
+            Some(1),
+            LogMessage {
+                level: LogLevel::Debug,
+                message: "line 1\nline 2".to_owned(),
+            },
+        )),
+        SolveProgress::Frame(pack(Some(1), vec!["#.".to_owned(), ".#".to_owned()])),
+        SolveProgress::SuccessResult(ResultPack {
+            verdict: Some(Verdict::Wrong("41".to_owned())),
+            ..pack(Some(1), Answer::Integer(42))
+        }),
+        SolveProgress::SuccessResult(ResultPack {
+            verdict: Some(Verdict::Correct),
+            ..pack(
+                Some(2),
+                Answer::grid(vec!["#".to_owned()], Some("I".to_owned())),
+            )
+        }),
+        SolveProgress::ErrorResult(pack(Some(2), "failed".to_owned())),
+        SolveProgress::Timeout(pack(Some(2), Duration::from_secs(5))),
+        SolveProgress::Cancelled(pack(None, ())),
+        SolveProgress::Error("solution not found".to_owned()),
+        SolveProgress::Done(pack(None, ())),
+        SolveProgress::Summary(SolveSummary {
+            days: 1,
+            solved_parts: 2,
+            duration: Duration::from_millis(3),
+            elapsed: Duration::from_millis(4),
+            failures: vec![(YearDay::new(2000, 1), Some(2), "failed".to_owned())],
+            wrong_answers: vec![(YearDay::new(2000, 1), 1)],
+        }),
+    ]
+}
+
+fn debug_strings(events: &[SolveProgress]) -> Vec<String> {
+    events.iter().map(|e| format!("{:?}", e)).collect()
+}
+
+#[test]
+fn recording_reads_back_the_recorded_events() {
+    let path = env::temp_dir().join(format!("aoc-recording-{}.jsonl", process::id()));
+    let inner = Arc::new(Mutex::new(LocalSyncStream::new()));
+    let mut tx = TeeSyncStream::create(Arc::clone(&inner), &path).unwrap();
+    for event in events() {
+        tx.send(event);
+    }
+    tx.close();
+
+    let recording = recording::load(&path);
+    _ = fs::remove_file(&path);
+    let recording = recording.unwrap();
+
+    let forwarded = inner.lock().unwrap().next_items().unwrap();
+    assert_eq!(debug_strings(&forwarded), debug_strings(&events()));
+    let loaded = recording
+        .iter()
+        .map(|r| r.progress.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(debug_strings(&loaded), debug_strings(&events()));
+    assert!(
+        recording.windows(2).all(|w| w[0].elapsed <= w[1].elapsed),
+        "the events should be recorded in order of time"
+    );
+
+    // Replaying sends the same sequence
+    let runner = ReplaySolutionRunner::new(Arc::new(recording), 1000.0);
+    let stream: Arc<Mutex<ChannelSyncStream>> = runner.run(YearDay::new(2000, 1), Input::Default);
+    let mut rx = stream.lock().unwrap().take_receiver().unwrap();
+    let mut replayed = Vec::new();
+    while let Some(event) = rx.recv() {
+        replayed.push(event);
+    }
+    assert_eq!(debug_strings(&replayed), debug_strings(&events()));
+}
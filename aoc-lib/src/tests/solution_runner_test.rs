@@ -0,0 +1,696 @@
+use crate::core::solution::*;
+use crate::core::solution_runner::*;
+use crate::util::YearDay;
+use futures::{executor, StreamExt};
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Solution whose parts behave as described by the lines of its input:
+/// "fail" returns an error, "loop" reports progress until interrupted, "slow" answers after 300 ms,
+/// "log" logs a message at each level, "frames" emits 100 frames and a final one, "panic" panics,
+/// "par-panic" panics in parallel code, anything else is the answer.
+#[derive(Default)]
+struct TestDay;
+impl Solution for TestDay {
+    fn info(&self) -> SolutionInfo {
+        SolutionInfo::new(2000, 1, "Test Day")
+    }
+
+    fn part1(&mut self, ctx: &Context) -> SolutionResult {
+        solve_line(ctx, 0)
+    }
+
+    fn part2(&mut self, ctx: &Context) -> SolutionResult {
+        solve_line(ctx, 1)
+    }
+}
+
+fn solve_line(ctx: &Context, index: usize) -> SolutionResult {
+    match ctx.input().lines().nth(index) {
+        Some("fail") => Err("failed on purpose".into()),
+        Some("slow") => {
+            thread::sleep(Duration::from_millis(300));
+            Ok("slow".into())
+        }
+        Some("log") => {
+            for level in [
+                LogLevel::Error,
+                LogLevel::Warn,
+                LogLevel::Info,
+                LogLevel::Debug,
+                LogLevel::Trace,
+            ] {
+                ctx.log(level, format!("{} message", level));
+            }
+            Ok("logged".into())
+        }
+        Some("frames") => {
+            for frame in 0..100 {
+                ctx.frame(|| vec![frame.to_string()]);
+            }
+            ctx.final_frame(|| vec!["final".to_owned()]);
+            Ok("framed".into())
+        }
+        Some("panic") => panic!("panicked on purpose"),
+        Some("par-panic") => {
+            let sum = (0..8)
+                .into_par_iter()
+                .map(|x| match x {
+                    5 => panic!("panicked in parallel on day {}", ctx.input().len()),
+                    x => x,
+                })
+                .sum::<i64>();
+            Ok(sum.into())
+        }
+        Some("loop") => loop {
+            ctx.progress(0.5);
+        },
+        Some(answer) => Ok(answer.into()),
+        None => Err("missing line".into()),
+    }
+}
+
+/// Variant of the test day answering "fast" for every part.
+#[derive(Default)]
+struct FastTestDay;
+impl Solution for FastTestDay {
+    fn info(&self) -> SolutionInfo {
+        SolutionInfo::new(2000, 1, "Test Day").with_variant("fast")
+    }
+
+    fn part1(&mut self, _ctx: &Context) -> SolutionResult {
+        Ok("fast".into())
+    }
+
+    fn part2(&mut self, _ctx: &Context) -> SolutionResult {
+        Ok("fast".into())
+    }
+}
+
+fn day(day: u32) -> YearDay {
+    YearDay::new(2000, day)
+}
+
+fn test_solutions(days: &[YearDay]) -> HashMap<YearDay, Vec<SolutionType>> {
+    days.iter()
+        .map(|year_day| (*year_day, vec![TestDay::as_type()]))
+        .collect()
+}
+
+/// Solves a test day on another thread, calling `on_event` with each event received.
+/// Returns the events once the stream is closed.
+fn solve_on_thread(
+    input: &str,
+    options: SolveOptions,
+    on_event: impl Fn(&SolveProgress),
+) -> Vec<SolveProgress> {
+    let stream = Arc::new(Mutex::new(ChannelSyncStream::new()));
+    let mut rx = stream.lock().unwrap().take_receiver().unwrap();
+    let input = Input::Custom(input.to_owned());
+    let handle = thread::spawn(move || {
+        run_solution_from(&test_solutions(&[day(1)]), day(1), input, stream, options)
+    });
+
+    let mut events = Vec::new();
+    while let Some(event) = rx.recv() {
+        on_event(&event);
+        events.push(event);
+    }
+    handle.join().unwrap();
+
+    events
+}
+
+#[test]
+fn cancellation_stops_the_running_part() {
+    let options = SolveOptions {
+        fps: 1000.0,
+        ..Default::default()
+    };
+    let token = options.cancellation_token.clone();
+    let events = solve_on_thread("loop\n2", options, |event| {
+        if let SolveProgress::Progress(_) = event {
+            token.cancel();
+        }
+    });
+
+    assert!(matches!(events.first(), Some(SolveProgress::Start(..))));
+    assert!(
+        matches!(events.last(), Some(SolveProgress::Cancelled(p)) if p.part == Some(1)),
+        "the stream should be closed right after the cancellation, got {:?}",
+        events.last()
+    );
+    assert!(!events
+        .iter()
+        .any(|e| matches!(e, SolveProgress::SuccessResult(_) | SolveProgress::Done(_))));
+}
+
+#[test]
+fn cancellation_before_start_skips_the_solution() {
+    let options = SolveOptions::default();
+    options.cancellation_token.cancel();
+    let events = solve_on_thread("1\n2", options, |_| ());
+
+    assert!(
+        matches!(events.as_slice(), [SolveProgress::Start(..), SolveProgress::Cancelled(p)] if p.part.is_none()),
+        "got {:?}",
+        events
+    );
+}
+
+/// Solves the test days with the given inputs, in order unless several jobs are used.
+/// Returns the events of all days.
+fn solve_days(inputs: &[(YearDay, &str)], options: SolveOptions) -> Vec<SolveProgress> {
+    let days = inputs
+        .iter()
+        .map(|(year_day, _)| *year_day)
+        .collect::<Vec<_>>();
+    let inputs = inputs.iter().copied().collect::<HashMap<_, _>>();
+    let solutions = test_solutions(&days);
+    let stream = Arc::new(Mutex::new(LocalSyncStream::new()));
+    run_many_with(
+        &days,
+        Arc::clone(&stream),
+        options,
+        |year_day, tx, options| {
+            let input = Input::Custom(inputs[&year_day].to_owned());
+            run_solution_from(&solutions, year_day, input, tx, options)
+        },
+    );
+
+    let mut stream = stream.lock().unwrap();
+    let events = stream.next_items().unwrap();
+    assert!(stream.next_items().is_none(), "the stream should be closed");
+
+    events
+}
+
+#[test]
+fn timeout_moves_on_to_the_next_day() {
+    let options = SolveOptions {
+        part_time_limit: Some(Duration::from_millis(50)),
+        ..Default::default()
+    };
+    let events = solve_days(&[(day(1), "loop\n2"), (day(2), "3\n4")], options);
+
+    let timeout = events
+        .iter()
+        .position(
+            |e| matches!(e, SolveProgress::Timeout(p) if p.year_day == day(1) && p.part == Some(1)),
+        )
+        .expect("day 1 should time out");
+    assert!(
+        matches!(&events[timeout + 1], SolveProgress::Done(p) if p.year_day == day(1)),
+        "the timed out day should be finished, got {:?}",
+        events[timeout + 1]
+    );
+    assert!(
+        matches!(&events[timeout + 2], SolveProgress::Start(year_day, _) if *year_day == day(2))
+    );
+    assert!(events.iter().any(
+        |e| matches!(e, SolveProgress::SuccessResult(p) if p.year_day == day(2) && p.part == Some(2))
+    ));
+}
+
+#[test]
+fn timeout_duration_is_measured_from_the_start_of_the_part() {
+    let options = SolveOptions {
+        part_time_limit: Some(Duration::from_millis(50)),
+        ..Default::default()
+    };
+    let events = solve_days(&[(day(1), "slow\nloop")], options);
+
+    let timeout = events.iter().find_map(|e| match e {
+        SolveProgress::Timeout(p) => Some(p),
+        _ => None,
+    });
+    assert!(
+        matches!(timeout, Some(p) if p.part == Some(2)
+            && p.value == Duration::from_millis(50)
+            && p.duration >= p.value
+            && p.duration < Duration::from_millis(300)),
+        "got {:?}",
+        timeout
+    );
+}
+
+fn summary(events: &[SolveProgress]) -> &SolveSummary {
+    match events.last() {
+        Some(SolveProgress::Summary(summary)) => summary,
+        last => panic!("the last event should be the summary, got {:?}", last),
+    }
+}
+
+#[test]
+fn run_many_runs_the_days_in_order() {
+    let events = solve_days(
+        &[(day(3), "1\n2"), (day(1), "3\n4"), (day(2), "5\n6")],
+        Default::default(),
+    );
+
+    let boundaries = events
+        .iter()
+        .filter_map(|e| match e {
+            SolveProgress::Start(year_day, _) => Some(("start", year_day.day)),
+            SolveProgress::Done(p) => Some(("done", p.year_day.day)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        boundaries,
+        [
+            ("start", 3),
+            ("done", 3),
+            ("start", 1),
+            ("done", 1),
+            ("start", 2),
+            ("done", 2)
+        ]
+    );
+    assert_eq!(summary(&events).days, 3);
+}
+
+#[test]
+fn run_many_continues_after_a_failing_day() {
+    let events = solve_days(
+        &[(day(1), "1\nfail"), (day(2), "fail\n2"), (day(3), "5\n6")],
+        Default::default(),
+    );
+
+    let answers = events
+        .iter()
+        .filter_map(|e| match e {
+            SolveProgress::SuccessResult(p) => Some((p.year_day.day, p.value.to_string())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        answers,
+        [
+            (1, "1".to_owned()),
+            (3, "5".to_owned()),
+            (3, "6".to_owned())
+        ]
+    );
+
+    let summary = summary(&events);
+    let durations = events
+        .iter()
+        .filter_map(|e| match e {
+            SolveProgress::Done(p) => Some(p.duration),
+            _ => None,
+        })
+        .sum::<Duration>();
+    assert_eq!(summary.days, 3);
+    assert_eq!(summary.solved_parts, 3);
+    assert_eq!(summary.duration, durations);
+    assert_eq!(
+        summary.failures,
+        [
+            (day(1), Some(2), "failed on purpose".to_owned()),
+            (day(2), Some(1), "failed on purpose".to_owned())
+        ]
+    );
+    assert!(summary.wrong_answers.is_empty());
+}
+
+#[test]
+fn run_many_sums_the_days_of_every_job() {
+    let options = SolveOptions {
+        jobs: 2,
+        ..Default::default()
+    };
+    let events = solve_days(
+        &[
+            (day(1), "fail\n2"),
+            (day(2), "3\n4"),
+            (day(3), "5\nfail"),
+            (day(4), "7\n8"),
+        ],
+        options,
+    );
+
+    let summary = summary(&events);
+    assert_eq!(summary.days, 4);
+    assert_eq!(summary.solved_parts, 5);
+    assert_eq!(
+        summary
+            .failures
+            .iter()
+            .map(|(year_day, part, _)| (year_day.day, *part))
+            .collect::<Vec<_>>(),
+        [(1, Some(1)), (3, Some(2))]
+    );
+}
+
+#[test]
+fn run_many_stops_without_summary_when_cancelled() {
+    let options = SolveOptions::default();
+    options.cancellation_token.cancel();
+    let events = solve_days(&[(day(1), "1\n2"), (day(2), "3\n4")], options);
+
+    assert!(
+        matches!(events.as_slice(), [SolveProgress::Start(..), SolveProgress::Cancelled(p)] if p.year_day == day(1)),
+        "got {:?}",
+        events
+    );
+}
+
+fn error_results(events: &[SolveProgress]) -> Vec<(u32, &str)> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            SolveProgress::ErrorResult(p) => Some((p.year_day.day, p.value.as_str())),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn panic_is_reported_as_the_error_of_its_part() {
+    let events = solve_days(
+        &[(day(1), "1\npanic"), (day(2), "3\n4")],
+        Default::default(),
+    );
+
+    let errors = error_results(&events);
+    assert_eq!(errors.len(), 1, "got {:?}", errors);
+    let (error_day, message) = errors[0];
+    assert_eq!(error_day, 1);
+    assert!(
+        message.starts_with("panicked at ") && message.ends_with(": panicked on purpose"),
+        "{}",
+        message
+    );
+    assert!(message.contains("solution_runner_test.rs"), "{}", message);
+    assert!(events.iter().any(
+        |e| matches!(e, SolveProgress::SuccessResult(p) if p.year_day == day(2) && p.part == Some(2))
+    ));
+
+    let summary = summary(&events);
+    assert_eq!(summary.days, 2);
+    assert_eq!(summary.solved_parts, 3);
+    assert_eq!(summary.failures.len(), 1);
+}
+
+#[test]
+fn panic_in_parallel_code_is_reported_with_its_location() {
+    let input = "par-panic\n2";
+    let events = solve_days(&[(day(1), input)], Default::default());
+
+    let errors = error_results(&events);
+    let expected = format!(": panicked in parallel on day {}", input.len());
+    assert!(
+        matches!(errors.as_slice(), [(1, message)] if message.starts_with("panicked at ")
+            && message.contains("solution_runner_test.rs")
+            && message.ends_with(&expected)),
+        "got {:?}",
+        errors
+    );
+}
+
+#[test]
+fn run_many_reports_a_panic_only_for_its_day() {
+    let options = SolveOptions {
+        jobs: 3,
+        ..Default::default()
+    };
+    let inputs = [
+        (day(1), "1\n2"),
+        (day(2), "par-panic\n4"),
+        (day(3), "5\npanic"),
+        (day(4), "7\n8"),
+        (day(5), "9\n90"),
+    ];
+    let events = solve_days(&inputs, options);
+
+    let errors = error_results(&events);
+    assert_eq!(
+        errors.iter().map(|(day, _)| *day).sorted().collect_vec(),
+        [2, 3],
+        "got {:?}",
+        errors
+    );
+    for (error_day, message) in errors {
+        let expected = match error_day {
+            2 => format!(": panicked in parallel on day {}", inputs[1].1.len()),
+            _ => ": panicked on purpose".to_owned(),
+        };
+        assert!(message.ends_with(&expected), "{}", message);
+    }
+
+    let answers = events
+        .iter()
+        .filter_map(|e| match e {
+            SolveProgress::SuccessResult(p) => Some((p.year_day.day, p.value.to_string())),
+            _ => None,
+        })
+        .sorted()
+        .collect_vec();
+    let expected = [
+        (1, "1"),
+        (1, "2"),
+        (3, "5"),
+        (4, "7"),
+        (4, "8"),
+        (5, "9"),
+        (5, "90"),
+    ];
+    assert_eq!(
+        answers,
+        expected.map(|(day, answer)| (day, answer.to_owned()))
+    );
+    assert_eq!(summary(&events).days, 5);
+}
+
+fn log_event(message: &str) -> SolveProgress {
+    SolveProgress::Log(ResultPack {
+        year_day: day(1),
+        part: None,
+        value: LogMessage {
+            level: LogLevel::Info,
+            message: message.to_owned(),
+        },
+        duration: Duration::default(),
+        verdict: None,
+    })
+}
+
+fn log_messages(events: &[SolveProgress]) -> Vec<&str> {
+    events
+        .iter()
+        .map(|e| match e {
+            SolveProgress::Log(p) => p.value.message.as_str(),
+            e => panic!("unexpected event {:?}", e),
+        })
+        .collect()
+}
+
+#[test]
+fn logs_reach_the_stream_up_to_the_configured_level() {
+    let logs = |log_level| {
+        let options = SolveOptions {
+            log_level,
+            ..Default::default()
+        };
+        let events = solve_days(&[(day(1), "log\n2")], options);
+        events
+            .iter()
+            .filter_map(|e| match e {
+                SolveProgress::Log(p) => {
+                    assert_eq!((p.year_day, p.part), (day(1), Some(1)));
+                    Some((p.value.level, p.value.message.clone()))
+                }
+                _ => None,
+            })
+            .collect_vec()
+    };
+
+    assert_eq!(
+        logs(LogLevel::Debug),
+        [
+            (LogLevel::Error, "ERROR message".to_owned()),
+            (LogLevel::Warn, "WARN message".to_owned()),
+            (LogLevel::Info, "INFO message".to_owned()),
+            (LogLevel::Debug, "DEBUG message".to_owned()),
+        ]
+    );
+    assert_eq!(logs(LogLevel::Warn).len(), 2);
+    assert_eq!(logs(LogLevel::Trace).len(), 5);
+}
+
+fn frames(events: &[SolveProgress]) -> Vec<&str> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            SolveProgress::Frame(p) => Some(p.value[0].as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn frames_are_only_emitted_when_enabled() {
+    let events = solve_days(&[(day(1), "frames\n2")], SolveOptions::default());
+    assert_eq!(frames(&events), Vec::<&str>::new());
+
+    let options = SolveOptions {
+        emit_frames: true,
+        fps: 1000.0,
+        ..Default::default()
+    };
+    let events = solve_days(&[(day(1), "frames\n2")], options);
+    assert_eq!(frames(&events).last(), Some(&"final"));
+}
+
+#[test]
+fn throttled_frames_keep_the_final_frame() {
+    let options = SolveOptions {
+        emit_frames: true,
+        fps: 1.0,
+        ..Default::default()
+    };
+    let events = solve_days(&[(day(1), "frames\n2")], options);
+
+    assert_eq!(frames(&events), ["0", "final"]);
+}
+
+#[test]
+fn channel_stream_recv_blocks_until_sent() {
+    let stream = Arc::new(Mutex::new(ChannelSyncStream::new()));
+    let mut rx = stream.lock().unwrap().take_receiver().unwrap();
+    assert!(stream.lock().unwrap().take_receiver().is_none());
+
+    let sender = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        let mut stream = stream.lock().unwrap();
+        stream.send(log_event("a"));
+        stream.send(log_event("b"));
+        stream.close();
+    });
+
+    let events = [rx.recv().unwrap(), rx.recv().unwrap()];
+    assert_eq!(log_messages(&events), ["a", "b"]);
+    assert!(rx.recv().is_none());
+    sender.join().unwrap();
+}
+
+#[test]
+fn channel_stream_recv_items_returns_available_items() {
+    let mut stream = ChannelSyncStream::new();
+    let mut rx = stream.take_receiver().unwrap();
+    stream.send(log_event("a"));
+    stream.send(log_event("b"));
+    assert!(stream.next_items().is_none());
+    assert_eq!(log_messages(&rx.recv_items().unwrap()), ["a", "b"]);
+
+    stream.send(log_event("c"));
+    stream.close();
+    stream.send(log_event("after close"));
+    assert_eq!(log_messages(&rx.recv_items().unwrap()), ["c"]);
+    assert!(rx.recv_items().is_none());
+}
+
+#[test]
+fn channel_stream_next_items_polls_until_closed() {
+    let mut stream = ChannelSyncStream::new();
+    assert_eq!(stream.next_items().map(|items| items.len()), Some(0));
+    stream.send(log_event("a"));
+    stream.close();
+    assert_eq!(log_messages(&stream.next_items().unwrap()), ["a"]);
+    assert!(stream.next_items().is_none());
+}
+
+#[test]
+fn channel_stream_async_stream_ends_after_close() {
+    let stream = Arc::new(Mutex::new(ChannelSyncStream::new()));
+    let rx = stream.lock().unwrap().take_receiver().unwrap();
+    let sender = thread::spawn(move || {
+        let mut stream = stream.lock().unwrap();
+        stream.send(log_event("a"));
+        stream.send(log_event("b"));
+        stream.close();
+    });
+
+    let events = executor::block_on(rx.collect::<Vec<_>>());
+    assert_eq!(log_messages(&events), ["a", "b"]);
+    sender.join().unwrap();
+}
+
+#[test]
+fn select_variant_prefers_the_default_variant() {
+    let solutions = [FastTestDay::as_type(), TestDay::as_type()];
+
+    let selected = |variant| select_variant(&solutions, variant).map(|s| s.info.variant.as_str());
+    assert_eq!(selected(None), Some(DEFAULT_VARIANT));
+    assert_eq!(selected(Some("fast")), Some("fast"));
+    assert_eq!(selected(Some(DEFAULT_VARIANT)), Some(DEFAULT_VARIANT));
+    assert_eq!(selected(Some("missing")), None);
+
+    let without_default = [FastTestDay::as_type()];
+    assert_eq!(
+        select_variant(&without_default, None).map(|s| s.info.variant.as_str()),
+        Some("fast"),
+        "the first variant is used without a default one"
+    );
+}
+
+/// Solves day 1 with both variants of the test day registered, returning the answers or the error.
+fn solve_variant(year_day: YearDay, variant: Option<&str>) -> Result<Vec<String>, String> {
+    let solutions = HashMap::from([(day(1), vec![TestDay::as_type(), FastTestDay::as_type()])]);
+    let stream = Arc::new(Mutex::new(LocalSyncStream::new()));
+    let options = SolveOptions {
+        variant: variant.map(|v| v.to_owned()),
+        ..Default::default()
+    };
+    run_solution_from(
+        &solutions,
+        year_day,
+        Input::Custom("1\n2".to_owned()),
+        Arc::clone(&stream),
+        options,
+    );
+
+    let events = stream.lock().unwrap().next_items().unwrap();
+    match events.iter().find_map(|e| match e {
+        SolveProgress::Error(err) => Some(err.to_owned()),
+        _ => None,
+    }) {
+        Some(err) => Err(err),
+        None => Ok(events
+            .iter()
+            .filter_map(|e| match e {
+                SolveProgress::SuccessResult(p) => Some(p.value.to_string()),
+                _ => None,
+            })
+            .collect()),
+    }
+}
+
+#[test]
+fn runner_solves_the_selected_variant() {
+    assert_eq!(
+        solve_variant(day(1), None),
+        Ok(vec!["1".to_owned(), "2".to_owned()])
+    );
+    assert_eq!(
+        solve_variant(day(1), Some("fast")),
+        Ok(vec!["fast".to_owned(), "fast".to_owned()])
+    );
+}
+
+#[test]
+fn runner_reports_an_unknown_variant_or_day() {
+    assert_eq!(
+        solve_variant(day(1), Some("missing")),
+        Err("solution variant not found".to_owned())
+    );
+    assert_eq!(
+        solve_variant(day(2), None),
+        Err("solution not found".to_owned())
+    );
+}
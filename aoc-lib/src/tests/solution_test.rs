@@ -0,0 +1,97 @@
+use crate::solution::*;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Progress handler collecting the reported values.
+struct CollectProgress(Arc<Mutex<Vec<f32>>>);
+impl ProgressHandler for CollectProgress {
+    fn on_progress(&mut self, value: f32) {
+        self.0.lock().unwrap().push(value);
+    }
+}
+
+fn context() -> (Context, Arc<Mutex<Vec<f32>>>) {
+    let values = Arc::new(Mutex::new(Vec::new()));
+    let ctx = Context {
+        progress_handler: Mutex::new(Box::new(CollectProgress(Arc::clone(&values)))),
+        ..Default::default()
+    };
+    (ctx, values)
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-6,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn nested_scopes_sum_to_the_whole() {
+    let (ctx, values) = context();
+    let scope = ctx.progress_scope(3);
+    for _ in 0..3 {
+        let sub_scope = scope.sub_scope(7);
+        for _ in 0..7 {
+            sub_scope.tick();
+        }
+    }
+
+    let values = values.lock().unwrap();
+    assert_eq!(values.len(), 21);
+    assert_close(values[6], 1.0 / 3.0);
+    assert_close(values[13], 2.0 / 3.0);
+    assert_close(values[20], 1.0);
+    assert!(values.windows(2).all(|w| w[0] < w[1]), "{:?}", values);
+}
+
+#[test]
+fn deeply_nested_scopes_still_report_progress() {
+    let (ctx, values) = context();
+    let mut scope = ctx.progress_scope(1000);
+    for _ in 0..10 {
+        scope = scope.sub_scope(1000);
+    }
+    scope.tick();
+    scope.tick();
+
+    let values = values.lock().unwrap();
+    assert!(values[0] > 0.0, "{:?}", values);
+    assert!(values[1] > values[0], "{:?}", values);
+}
+
+#[test]
+fn progress_is_reported_from_several_threads() {
+    let (ctx, values) = context();
+    let scope = ctx.progress_scope(1000);
+    thread::scope(|s| {
+        for _ in 0..8 {
+            let scope = scope.clone();
+            s.spawn(move || {
+                for _ in 0..125 {
+                    scope.tick();
+                }
+            });
+        }
+    });
+
+    let mut values = values.lock().unwrap().clone();
+    assert_eq!(values.len(), 1000);
+    values.sort_by(f32::total_cmp);
+    for (index, value) in values.iter().enumerate() {
+        assert_close(*value, (index + 1) as f32 / 1000.0);
+    }
+}
+
+#[test]
+fn answers_too_large_for_integer_are_text() {
+    assert_eq!(Answer::from(42u64), Answer::Integer(42));
+    assert_eq!(Answer::from(i64::MAX as u64), Answer::Integer(i64::MAX));
+    assert_eq!(Answer::from(u64::MAX), Answer::Text(u64::MAX.to_string()));
+    assert_eq!(
+        Answer::from(usize::MAX),
+        Answer::Text(usize::MAX.to_string())
+    );
+}
@@ -2,7 +2,7 @@ use crate::{core::solution::*, inputs, util::YearDay};
 use regex::Regex;
 
 pub fn assert_result(result: SolutionResult, expected: &str, message: &str) {
-    match result {
+    match result.map(|answer| answer.to_string()) {
         Ok(actual) if &actual == expected => (),
         Ok(actual) => {
             eprintln!(
@@ -53,7 +53,7 @@ where
 {
     let SolutionInfo { year, day, .. } = T::as_type().info;
     let year_day = YearDay::new(year, day);
-    let input = inputs::get(&year_day).expect("input should be available");
+    let input = inputs::get(&year_day, inputs::DEFAULT_PROFILE).expect("input should be available");
 
     return setup::<T>(&input);
 }
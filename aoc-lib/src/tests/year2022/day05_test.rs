@@ -4,7 +4,7 @@ use crate::tests::util::*;
 
 #[test]
 fn example_input() {
-    let (mut day, ctx) = setup::<Day05>(
+    let (mut day, ctx) = setup::<ParseOnce<Day05>>(
         r#"
     [D]    
 [N] [C]    
@@ -22,7 +22,7 @@ move 1 from 1 to 2"#,
 
 #[test]
 fn puzzle_input() {
-    let (mut day, ctx) = setup_from_file::<Day05>();
+    let (mut day, ctx) = setup_from_file::<ParseOnce<Day05>>();
     assert_result(day.part1(&ctx), "PTWLTDSJV", "solve part 1");
     assert_result(day.part2(&ctx), "WZMFVGGZP", "solve part 2");
 }
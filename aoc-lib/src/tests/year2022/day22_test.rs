@@ -4,7 +4,7 @@ use crate::tests::util::*;
 
 #[test]
 fn example_input() {
-    let (mut day, ctx) = setup::<Day22>(
+    let (mut day, ctx) = setup::<ParseOnce<Day22>>(
         r#"
         ...#
         .#..
@@ -25,9 +25,16 @@ fn example_input() {
     assert_result(day.part2(&ctx), "5031", "solve part 2");
 }
 
+#[test]
+fn cube_is_only_needed_by_part_2() {
+    let (mut day, ctx) = setup::<ParseOnce<Day22>>("....\n\n2");
+    assert_result(day.part1(&ctx), "1012", "solve part 1");
+    assert!(day.part2(&ctx).is_err());
+}
+
 #[test]
 fn puzzle_input() {
-    let (mut day, ctx) = setup_from_file::<Day22>();
+    let (mut day, ctx) = setup_from_file::<ParseOnce<Day22>>();
     assert_result(day.part1(&ctx), "162186", "solve part 1");
     assert_result(day.part2(&ctx), "55267", "solve part 2");
 }
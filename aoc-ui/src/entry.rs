@@ -1,10 +1,10 @@
 use crate::{
     config::{self, Config},
-    state::UiState,
+    state::{RunMode, UiState},
 };
 use bracket_terminal::prelude::*;
 
-pub fn main() -> BResult<()> {
+pub fn main(run_mode: RunMode) -> BResult<()> {
     let config = config::default();
     let Config {
         width,
@@ -24,7 +24,7 @@ pub fn main() -> BResult<()> {
         .with_advanced_input(true)
         .build()?;
 
-    let gs = UiState::new(config);
+    let gs = UiState::new(config, run_mode);
     main_loop(context, gs)?;
 
     Ok(())
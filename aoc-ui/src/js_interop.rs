@@ -1,17 +1,27 @@
 use crate::entry;
 use aoc::{
-    core::solution_runner::{self, Input, LocalSyncStream, SolveProgress, SyncStream},
+    core::solution_runner::{
+        self, Input, LocalSyncStream, ResultPack, RunningStep, SolveOptions, SolveProgress,
+        SolveSummary, SyncStream,
+    },
     helpers::AsSome,
+    solution::LogLevel,
     util::YearDay,
 };
 use bracket_terminal::prelude::PointF;
+use itertools::Itertools;
+use js_sys::Function;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::{
+    mem,
     ops::Deref,
-    sync::{Arc, Mutex},
+    panic,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
 };
 use wasm_bindgen::prelude::*;
+use wasm_timer::SystemTime;
 use web_sys::{DedicatedWorkerGlobalScope, Worker};
 
 pub struct WorkerWrapper(Worker);
@@ -23,6 +33,15 @@ impl Deref for WorkerWrapper {
     }
 }
 
+pub struct FunctionWrapper(Function);
+unsafe impl Send for FunctionWrapper {}
+impl Deref for FunctionWrapper {
+    type Target = Function;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 pub struct WorkerScopeWrapper(DedicatedWorkerGlobalScope);
 unsafe impl Send for WorkerScopeWrapper {}
 impl Deref for WorkerScopeWrapper {
@@ -42,6 +61,19 @@ pub enum TouchKind {
 #[derive(Serialize, Deserialize)]
 pub enum WorkerCommand {
     StartDay(YearDay, Input),
+    StartDays(Vec<YearDay>),
+}
+
+/// Days of a [`WorkerCommand::StartDays`] command, which are resumed by a new worker
+/// if the current one is aborted by a panic.
+#[derive(Default)]
+pub struct WorkerBatch {
+    /// Days not finished yet.
+    pub remaining: Vec<YearDay>,
+    /// Totals of the days finished by the current worker.
+    pub summary: SolveSummary,
+    /// Totals of the days finished by the aborted workers.
+    pub aborted_summary: SolveSummary,
 }
 
 #[derive(Default)]
@@ -50,8 +82,16 @@ pub struct JsBridge {
     pub unhandled_keys: Vec<String>,
     pub unhandled_touches: Vec<(PointF, TouchKind)>,
     pub worker_tx: Option<Arc<Mutex<LocalSyncStream>>>,
+    pub worker_year_day: Option<YearDay>,
+    pub worker_batch: Option<WorkerBatch>,
+    /// Step being solved by the worker, timed on the main thread as the worker cannot unwind.
+    pub worker_step: Option<RunningStep>,
+    pub worker_part_time_limit: Option<Duration>,
     pub worker_wrapper: Option<WorkerWrapper>,
+    pub restart_worker_wrapper: Option<FunctionWrapper>,
     pub worker_scope_wrapper: Option<WorkerScopeWrapper>,
+    /// Day and input submitted on the web page, waiting to be solved.
+    pub custom_input_request: Option<(YearDay, String)>,
 }
 pub static JS_BRIDGE: Lazy<Mutex<JsBridge>> = Lazy::new(|| Mutex::new(Default::default()));
 
@@ -62,6 +102,15 @@ pub fn worker_set_global_scope(worker_scope: JsValue) {
     JS_BRIDGE.lock().unwrap().worker_scope_wrapper = Some(WorkerScopeWrapper(
         DedicatedWorkerGlobalScope::from(worker_scope),
     ));
+
+    // Panics cannot be caught in WASM, so the running day is finished before the worker aborts
+    panic::set_hook(Box::new(|info| {
+        console_error_panic_hook::hook(info);
+        if let Some(step) = solution_runner::running_step() {
+            let stream = Arc::new(Mutex::new(WorkerSyncStream {}));
+            solution_runner::report_panic(&stream, step, &solution_runner::panic_message(info));
+        }
+    }));
 }
 
 struct WorkerSyncStream {}
@@ -86,33 +135,194 @@ pub fn worker_on_message(message: JsValue) {
     let message = message.as_string().unwrap();
     let command: WorkerCommand = serde_json::from_str(&message).unwrap();
 
+    let stream = Arc::new(Mutex::new(WorkerSyncStream {}));
+    // No part time limit, as the timeout could not unwind the solution. See check_worker_deadline.
+    let options = SolveOptions {
+        fps: 2.1,
+        log_level: LogLevel::Trace,
+        ..Default::default()
+    };
     match command {
-        WorkerCommand::StartDay(year_day, _input) => {
-            let stream = Arc::new(Mutex::new(WorkerSyncStream {}));
-            solution_runner::run_solution(year_day, Input::Default, stream, 2.1);
+        WorkerCommand::StartDay(year_day, input) => {
+            solution_runner::run_solution(year_day, input, stream, options);
         }
+        WorkerCommand::StartDays(days) => solution_runner::run_many(&days, stream, options),
     };
 }
 
 // --- Main ---
 
+/// Calls the JS callback which creates a new worker and registers it via [`set_worker`].
+pub fn restart_worker() {
+    let restart_worker = JS_BRIDGE
+        .lock()
+        .unwrap()
+        .restart_worker_wrapper
+        .as_ref()
+        .map(|f| f.0.clone());
+    if let Some(restart_worker) = restart_worker {
+        restart_worker.call0(&JsValue::NULL).unwrap();
+    }
+}
+
+#[wasm_bindgen]
+pub fn set_restart_worker(restart_worker: JsValue) {
+    JS_BRIDGE.lock().unwrap().restart_worker_wrapper =
+        Some(FunctionWrapper(Function::from(restart_worker)));
+}
+
+/// Registers a new worker, which resumes the days left by the previous one if it was aborted.
 #[wasm_bindgen]
 pub fn set_worker(worker: JsValue) {
-    JS_BRIDGE.lock().unwrap().worker_wrapper = Some(WorkerWrapper(Worker::from(worker)));
+    let worker = WorkerWrapper(Worker::from(worker));
+    let mut bridge = JS_BRIDGE.lock().unwrap();
+    if let Some(batch) = &bridge.worker_batch {
+        let command = WorkerCommand::StartDays(batch.remaining.clone());
+        worker
+            .post_message(&JsValue::from_str(
+                &serde_json::to_string(&command).unwrap(),
+            ))
+            .unwrap();
+    }
+    bridge.worker_wrapper = Some(worker);
+}
+
+/// Replaces the worker aborted by a panic. The days it has not finished are resumed by the new worker.
+#[wasm_bindgen]
+pub fn on_worker_panicked() {
+    abort_worker(JS_BRIDGE.lock().unwrap());
+}
+
+/// Reports a timeout and replaces the worker if its step has exceeded the part time limit.
+pub fn check_worker_deadline() {
+    let mut bridge = JS_BRIDGE.lock().unwrap();
+    let (step, limit) = match (bridge.worker_step, bridge.worker_part_time_limit) {
+        (Some(step), Some(limit))
+            if SystemTime::now()
+                .duration_since(step.part_start)
+                .unwrap_or_default()
+                > limit =>
+        {
+            (step, limit)
+        }
+        _ => return,
+    };
+
+    let stream = Arc::new(Mutex::new(LocalSyncStream::new()));
+    solution_runner::report_timeout(&stream, step, limit);
+    let items = stream.lock().unwrap().next_items().unwrap_or_default();
+    for progress in items {
+        forward_progress(&mut bridge, progress);
+    }
+    abort_worker(bridge);
+}
+
+/// Terminates the worker, then starts a new one which resumes the days it has not finished.
+fn abort_worker(mut bridge: MutexGuard<JsBridge>) {
+    if let Some(worker) = bridge.worker_wrapper.take() {
+        worker.terminate();
+    }
+    bridge.worker_step = None;
+    if let Some(batch) = &mut bridge.worker_batch {
+        // The days run one by one in the worker, so its elapsed time is the sum of their runtimes
+        let mut summary = mem::take(&mut batch.summary);
+        summary.elapsed = summary.duration;
+        batch.aborted_summary.merge(summary);
+    }
+    bridge.worker_year_day = bridge
+        .worker_batch
+        .as_ref()
+        .and_then(|batch| batch.remaining.first().copied());
+    if bridge.worker_year_day.is_none() {
+        let batch = bridge.worker_batch.take();
+        if let Some(stream) = bridge.worker_tx.take() {
+            let mut stream = stream.lock().unwrap();
+            if let Some(batch) = batch {
+                stream.send(SolveProgress::Summary(batch.aborted_summary));
+            }
+            stream.close();
+        }
+    }
+
+    drop(bridge);
+    restart_worker();
 }
 
 #[wasm_bindgen]
 pub fn on_worker_message(msg: JsValue) {
     let s = &msg.as_string().unwrap();
     let progress: SolveProgress = serde_json::from_str(s).unwrap();
-    JS_BRIDGE
-        .lock()
-        .unwrap()
-        .worker_tx
-        .as_some()
-        .lock()
-        .unwrap()
-        .send(progress);
+    forward_progress(&mut JS_BRIDGE.lock().unwrap(), progress);
+}
+
+/// Sends an event of the worker to the stream of the run, keeping track of the running step.
+fn forward_progress(bridge: &mut JsBridge, progress: SolveProgress) {
+    // Keep track of the running day, so a cancellation can be reported for it
+    if let SolveProgress::Start(year_day, _) = &progress {
+        bridge.worker_year_day = Some(*year_day);
+    }
+    let now = SystemTime::now();
+    bridge.worker_step = match (&progress, bridge.worker_step) {
+        (SolveProgress::Start(year_day, _), _) => Some(RunningStep {
+            year_day: *year_day,
+            part: None,
+            day_start: now,
+            part_start: now,
+        }),
+        (SolveProgress::Initialized(_), Some(step)) => Some(RunningStep {
+            part: Some(1),
+            part_start: now,
+            ..step
+        }),
+        (
+            SolveProgress::SuccessResult(ResultPack { part, .. })
+            | SolveProgress::ErrorResult(ResultPack { part, .. }),
+            Some(step),
+        ) => Some(RunningStep {
+            part: part.map(|p| p + 1),
+            part_start: now,
+            ..step
+        }),
+        (SolveProgress::Done(_) | SolveProgress::Cancelled(_), _) => None,
+        (_, step) => step,
+    };
+    let year_day = bridge.worker_year_day.unwrap_or_default();
+    let progress = match (bridge.worker_batch.as_mut(), progress) {
+        // The days finished by the aborted workers are missing from the summary of the last one
+        (Some(_), SolveProgress::Summary(summary)) => {
+            let mut batch = bridge.worker_batch.take().unwrap();
+            batch.aborted_summary.merge(summary);
+            SolveProgress::Summary(batch.aborted_summary)
+        }
+        (Some(batch), progress) => {
+            batch.summary.record(year_day, &progress);
+            if let SolveProgress::Done(p) = &progress {
+                batch.remaining.retain(|d| *d != p.year_day);
+            }
+            progress
+        }
+        (None, progress) => progress,
+    };
+    bridge.worker_tx.as_some().lock().unwrap().send(progress);
+}
+
+/// Lists the years that have solutions, newest first, to choose the year of a custom input.
+#[wasm_bindgen]
+pub fn solution_years() -> Vec<i32> {
+    aoc::solutions::create_list()
+        .into_iter()
+        .map(|s| s.info.year)
+        .unique()
+        .sorted_by(|a, b| b.cmp(a))
+        .collect()
+}
+
+/// Requests solving a single day with the given puzzle input.
+#[wasm_bindgen]
+pub fn solve_custom_input(year: JsValue, day: JsValue, input: JsValue) {
+    let year_day = YearDay::new(year.as_f64().unwrap() as i32, day.as_f64().unwrap() as u32);
+    let input = input.as_string().unwrap();
+    JS_BRIDGE.lock().unwrap().custom_input_request = Some((year_day, input));
 }
 
 #[wasm_bindgen]
@@ -149,7 +359,7 @@ pub fn push_touch_event(x: JsValue, y: JsValue, kind: JsValue) {
 #[wasm_bindgen]
 pub fn main_wasm() -> Result<(), JsValue> {
     console_error_panic_hook::set_once();
-    entry::main().map_err(|x| JsValue::from(format!("{:?}", x)))?;
+    entry::main(Default::default()).map_err(|x| JsValue::from(format!("{:?}", x)))?;
 
     Ok(())
 }
@@ -1,3 +1,4 @@
 pub mod flake_text_manager;
+pub mod log_manager;
 pub mod snowflake_manager;
 pub mod ui_text_manager;
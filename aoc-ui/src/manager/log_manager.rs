@@ -0,0 +1,105 @@
+use crate::config::Config;
+use aoc::{
+    core::solution_runner::{LogMessage, ResultPack},
+    solution::LogLevel,
+};
+use bracket_terminal::prelude::*;
+use std::collections::VecDeque;
+
+static LOG_ROWS: i32 = 8;
+static MAX_LINES: usize = 200;
+static BORDER_COLOR: (u8, u8, u8, u8) = (96, 96, 128, 255);
+static PANE_BG_COLOR: (u8, u8, u8, u8) = (10, 10, 25, 255);
+
+/// Pane at the bottom of the screen showing the log messages of the solutions.
+#[derive(Default)]
+pub struct LogManager {
+    lines: VecDeque<(LogLevel, String)>,
+    /// Least important level to display, or None if the pane is hidden.
+    level: Option<LogLevel>,
+}
+impl LogManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, pack: &ResultPack<LogMessage>) {
+        let LogMessage { level, message } = &pack.value;
+        for line in message.lines() {
+            let line = format!(
+                "#{: <2} {: <5} {}",
+                pack.year_day.day,
+                level.to_string(),
+                line
+            );
+            self.lines.push_back((*level, line));
+        }
+        while self.lines.len() > MAX_LINES {
+            self.lines.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Cycles between hidden, info, debug and trace verbosity.
+    pub fn cycle_level(&mut self) {
+        self.level = match self.level {
+            None => Some(LogLevel::Info),
+            Some(LogLevel::Info) => Some(LogLevel::Debug),
+            Some(LogLevel::Debug) => Some(LogLevel::Trace),
+            Some(_) => None,
+        };
+    }
+
+    pub fn draw(&self, config: &Config, batch: &mut DrawBatch) {
+        let level = match self.level {
+            Some(level) => level,
+            None => return,
+        };
+        let width = config.width as i32;
+        let top = config.height as i32 - LOG_ROWS - 1;
+        let border = ColorPair::new(BORDER_COLOR, PANE_BG_COLOR);
+        batch.fill_region(
+            Rect::with_size(0, top, width, LOG_ROWS + 1),
+            ColorPair::new(BORDER_COLOR, PANE_BG_COLOR),
+            to_cp437(' '),
+        );
+        batch.print_color(
+            Point::new(0, top),
+            format!(
+                "{:─<width$}",
+                format!("─Log ({}) - Tab to change ", level),
+                width = width as usize
+            ),
+            border,
+        );
+
+        let visible_lines = self
+            .lines
+            .iter()
+            .filter(|(l, _)| *l <= level)
+            .rev()
+            .take(LOG_ROWS as usize)
+            .collect::<Vec<_>>();
+        for (i, (l, line)) in visible_lines.into_iter().rev().enumerate() {
+            let line = line.chars().take(width as usize - 2).collect::<String>();
+            batch.print_color(
+                Point::new(1, top + 1 + i as i32),
+                line,
+                ColorPair::new(level_color(*l), PANE_BG_COLOR),
+            );
+        }
+    }
+}
+
+fn level_color(level: LogLevel) -> (u8, u8, u8, u8) {
+    match level {
+        LogLevel::Error => (255, 85, 85, 255),
+        LogLevel::Warn => (255, 204, 0, 255),
+        LogLevel::Info => (216, 216, 216, 255),
+        LogLevel::Debug => (150, 150, 150, 255),
+        LogLevel::Trace => (110, 110, 110, 255),
+    }
+}
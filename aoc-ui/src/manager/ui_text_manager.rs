@@ -14,7 +14,10 @@ use crate::{
     state::BG_COLOR,
     util::distance2d_pythagoras_f32,
 };
-use aoc::{core::solution_runner::SolveProgress, util::fmt_duration_wasm};
+use aoc::{
+    answers::Verdict, core::solution_runner::SolveProgress, solution::Answer,
+    util::fmt_duration_wasm,
+};
 use bracket_terminal::prelude::*;
 use itertools::Itertools;
 use rand::Rng;
@@ -27,6 +30,8 @@ use std::{
 #[allow(dead_code)]
 static ROW_COUNT: usize = 45;
 static COLUMN_WIDTH: usize = 29;
+static ROWS_PER_DAY: usize = 5;
+static ANSWER_OFFSET: usize = 9;
 static TIME_PER_CHAR: f32 = 25.0;
 // static TIME_PER_CHAR: f32 = 2.0;
 static FLAKE_CHAR_MOVE_TIME: f32 = 2000.0;
@@ -41,6 +46,9 @@ static TITLE_COLOR: (u8, u8, u8, u8) = (230, 230, 230, 255);
 // static TITLE_COLOR: (u8, u8, u8, u8) = (204, 204, 204, 255);
 // static SOLUTION_COLOR: (u8, u8, u8, u8) = (255, 255, 102, 255);
 static SOLUTION_COLOR: (u8, u8, u8, u8) = (255, 255, 200, 255);
+static WRONG_COLOR: (u8, u8, u8, u8) = (255, 85, 85, 255);
+static GRID_BORDER_COLOR: (u8, u8, u8, u8) = (96, 96, 128, 255);
+static GRID_BG_COLOR: (u8, u8, u8, u8) = (10, 10, 25, 255);
 
 enum QueueItem {
     Progress(SolveProgress),
@@ -48,14 +56,23 @@ enum QueueItem {
     SnowyLine(FlakeCharLine),
 }
 
+/// Grid answer without a recognized text, drawn at full size in its own pane
+/// as it does not fit in the row of its part.
+struct GridPane {
+    title: String,
+    lines: Vec<String>,
+    color: (u8, u8, u8, u8),
+}
+
 pub struct UiTextManager {
     top_left: Point,
-    _config: Rc<RefCell<Config>>,
+    config: Rc<RefCell<Config>>,
     snowy_lines: Vec<FlakeCharLine>,
     typed_lines: HashMap<u32, Vec<Option<TypedLineAnimation>>>,
     snowflake_manager: Rc<RefCell<SnowflakeManager>>,
     draw_queue: VecDeque<QueueItem>,
     balloons: Vec<AnimatedItem<Balloon>>,
+    grid: Option<GridPane>,
     speed: f32,
 }
 impl UiTextManager {
@@ -65,13 +82,14 @@ impl UiTextManager {
         top_left: Point,
     ) -> Self {
         let manager = UiTextManager {
-            _config: config,
+            config,
             top_left,
             snowflake_manager,
             draw_queue: VecDeque::new(),
             snowy_lines: Vec::new(),
             typed_lines: HashMap::new(),
             balloons: Vec::new(),
+            grid: None,
             speed: 1.0,
         };
         manager
@@ -79,6 +97,7 @@ impl UiTextManager {
 
     pub fn clear(&mut self) {
         self.snowy_lines.clear();
+        self.grid = None;
         for (_, typed_line) in self.typed_lines.iter_mut() {
             for i in 0..typed_line.len() {
                 typed_line[i] = None;
@@ -151,6 +170,49 @@ impl UiTextManager {
         } else {
             self.balloons.clear();
         }
+
+        self.draw_grid(batch);
+    }
+
+    /// Draws the last grid answer in a pane at the bottom right corner.
+    fn draw_grid(&self, batch: &mut DrawBatch) {
+        let GridPane {
+            title,
+            lines,
+            color,
+        } = match &self.grid {
+            Some(grid) => grid,
+            None => return,
+        };
+        let config = self.config.borrow();
+        let width = lines
+            .iter()
+            .map(|l| l.chars().count())
+            .chain([title.chars().count()])
+            .max()
+            .unwrap() as i32
+            + 2;
+        let height = lines.len() as i32 + 2;
+        let left = config.width as i32 - width;
+        let top = config.height as i32 - height;
+        let border = ColorPair::new(GRID_BORDER_COLOR, GRID_BG_COLOR);
+        batch.fill_region(
+            Rect::with_size(left, top, width, height),
+            border,
+            to_cp437(' '),
+        );
+        batch.print_color(
+            Point::new(left, top),
+            format!("{:─<width$}", title, width = width as usize),
+            border,
+        );
+        for (i, line) in lines.iter().enumerate() {
+            batch.print_color(
+                Point::new(left + 1, top + 1 + i as i32),
+                line,
+                ColorPair::new(*color, GRID_BG_COLOR),
+            );
+        }
     }
 
     fn handle_balloons(&mut self, ctx: &BTerm, fancy_batch: &mut DrawBatch) {
@@ -261,7 +323,11 @@ impl UiTextManager {
                     TIME_PER_CHAR,
                 );
             }
+            SolveProgress::Initialized(_) => (),
+            SolveProgress::Log(_) => (),
+            SolveProgress::Frame(_) => (),
             SolveProgress::Error(_) => (),
+            SolveProgress::Summary(_) => (),
             SolveProgress::Progress(pack) => {
                 let line = format!(
                     "{}Part {}► {: >5.2}% {: >8}",
@@ -283,42 +349,83 @@ impl UiTextManager {
                 );
             }
             SolveProgress::SuccessResult(pack) => {
+                // Mark the answer if it can be compared with the confirmed one
+                let (mark, color, solution_color) = match &pack.verdict {
+                    Some(Verdict::Correct) => ('√', PART_COLOR, SOLUTION_COLOR),
+                    Some(Verdict::Wrong(_)) => ('x', WRONG_COLOR, WRONG_COLOR),
+                    Some(Verdict::Unknown) | None => (':', PART_COLOR, SOLUTION_COLOR),
+                };
                 let line = format!(
-                    "{}Part {}:",
+                    "{}Part {}{}",
                     if pack.part.unwrap() == 1 {
                         "├"
                     } else {
                         "└"
                     },
                     pack.part.unwrap(),
+                    mark,
                     // format!("({})", fmt_duration_s(&pack.duration)),
                 );
-                self.add_typed_line(
-                    line,
-                    pack.year_day.day,
-                    pack.part,
-                    PART_COLOR,
-                    TIME_PER_CHAR,
-                );
+                self.add_typed_line(line, pack.year_day.day, pack.part, color, TIME_PER_CHAR);
 
-                let y = (pack.year_day.day - 1) as usize * 5;
+                let y = (pack.year_day.day - 1) as usize * ROWS_PER_DAY;
                 let base_pos =
                     self.top_left + Point::new((y / ROW_COUNT) * COLUMN_WIDTH, y % ROW_COUNT);
                 let y = base_pos.y + pack.part.unwrap() as i32;
-                let x = base_pos.x + 9;
+                let x = base_pos.x + ANSWER_OFFSET as i32;
 
+                let answer = match &pack.value {
+                    Answer::Grid { lines, text: None } => {
+                        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+                        let title =
+                            format!("─Day {} part {} ", pack.year_day.day, pack.part.unwrap());
+                        self.grid = Some(GridPane {
+                            title,
+                            lines: lines.clone(),
+                            color: solution_color,
+                        });
+                        format!("{}x{} grid", width, lines.len())
+                    }
+                    answer => answer.to_string(),
+                };
                 let mut flake_line = FlakeCharLine::new(
                     PointF::new(x as f32, y as f32),
                     FLAKE_CHAR_MOVE_TIME * self.speed,
                     FLAKE_CHAR_FADE_OUT_TIME * self.speed,
                     FLAKE_CHAR_FADE_IN_TIME * self.speed,
-                    SOLUTION_COLOR,
+                    solution_color,
                 );
 
-                pack.value.chars().for_each(|c| flake_line.add_char(c));
+                answer
+                    .chars()
+                    .take(COLUMN_WIDTH - ANSWER_OFFSET)
+                    .for_each(|c| flake_line.add_char(c));
                 self.draw_queue.push_back(QueueItem::SnowyLine(flake_line));
             }
             SolveProgress::ErrorResult(_) => (),
+            SolveProgress::Timeout(pack) => {
+                let part = pack.part.unwrap_or(1);
+                self.add_typed_line(
+                    format!(
+                        "{}Part {}: timeout",
+                        if part == 1 { "├" } else { "└" },
+                        part
+                    ),
+                    pack.year_day.day,
+                    Some(part),
+                    PART_COLOR,
+                    TIME_PER_CHAR,
+                );
+            }
+            SolveProgress::Cancelled(pack) => {
+                self.add_typed_line(
+                    "(cancelled)".to_owned(),
+                    pack.year_day.day,
+                    Some(3),
+                    (100, 100, 100, 255),
+                    TIME_PER_CHAR,
+                );
+            }
             SolveProgress::Done(pack) => {
                 self.add_typed_line(
                     format!("({})", fmt_duration_wasm(&pack.duration)),
@@ -344,7 +451,7 @@ impl UiTextManager {
         base_speed: f32,
     ) {
         let part = part.unwrap() as usize;
-        let y = (day - 1) as usize * 5;
+        let y = (day - 1) as usize * ROWS_PER_DAY;
         let base_pos = self.top_left + Point::new((y / ROW_COUNT) * COLUMN_WIDTH, y % ROW_COUNT);
         let len = line.len() as f32;
 
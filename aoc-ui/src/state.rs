@@ -3,16 +3,24 @@ use crate::{
     config::Config,
     js_interop::{self, TouchKind, JS_BRIDGE},
     manager::{
-        flake_text_manager::FlakeCharLine, snowflake_manager::SnowflakeManager,
-        ui_text_manager::UiTextManager,
+        flake_text_manager::FlakeCharLine, log_manager::LogManager,
+        snowflake_manager::SnowflakeManager, ui_text_manager::UiTextManager,
     },
     util::get_mouse_tile_pos,
     wasm_runner::WasmRunner,
 };
+#[cfg(not(target_arch = "wasm32"))]
+use aoc::core::hot_reload::{self, HotReloadSolutionRunner};
 use aoc::{
-    core::solution_runner::{
-        LocalSyncStream, SolutionRunner, SolveProgress, SyncStream, ThreadSolutionRunner,
+    core::{
+        recording::{RecordedProgress, RecordingSolutionRunner, ReplaySolutionRunner},
+        solution_runner::{
+            Input, LocalSyncStream, SolutionRunner, SolveOptions, SolveProgress, SyncStream,
+            ThreadSolutionRunner,
+        },
     },
+    helpers::is_wasm,
+    solution::{LogLevel, SolutionType},
     solutions,
     util::YearDay,
 };
@@ -21,11 +29,30 @@ use itertools::Itertools;
 use rand::Rng;
 use std::{
     cell::RefCell,
+    path::PathBuf,
     rc::Rc,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 pub static BG_COLOR: (u8, u8, u8, u8) = (15, 15, 35, 255);
+/// Time limit of each part, after which the part is reported as timed out and the next day is solved.
+static PART_TIME_LIMIT: Duration = Duration::from_secs(60);
+
+/// Source of the events displayed by the UI.
+#[derive(Clone, Default)]
+pub enum RunMode {
+    /// Solve the days, in the web worker if available.
+    #[default]
+    Solve,
+    /// Solve the days natively, recording each run to the given path.
+    Record(PathBuf),
+    /// Play back a recorded run at the given speed without solving anything, e.g. as a demo.
+    Replay(Arc<Vec<RecordedProgress>>, f32),
+    /// Solve the days natively with aoc-lib built as a dynamic library at the given path,
+    /// reloading it whenever it is rebuilt. Not supported in WASM.
+    HotReload(PathBuf),
+}
 
 enum SolveState {
     NotSolved,
@@ -35,12 +62,17 @@ enum SolveState {
 
 pub struct UiState {
     config: Rc<RefCell<Config>>,
+    run_mode: RunMode,
     total_time: f32,
     snowflake_manager: Rc<RefCell<SnowflakeManager>>,
     text_manager: FlakeCharLine,
     solve_stream: Option<Arc<Mutex<LocalSyncStream>>>,
+    solve_runner: Option<Box<dyn SolutionRunner<LocalSyncStream>>>,
     ui_text_manager: UiTextManager,
+    log_manager: LogManager,
     solve_state: SolveState,
+    /// All days are being solved, finishing with a summary instead of the first day's Done event.
+    solve_all_days: bool,
     touch_mode: bool,
 }
 impl GameState for UiState {
@@ -68,6 +100,8 @@ impl GameState for UiState {
         self.ui_text_manager
             .tick(ctx, &mut normal_batch, &mut fancy_batch);
         self.print_status(ctx, &mut normal_batch);
+        self.log_manager
+            .draw(&self.config.borrow(), &mut normal_batch);
 
         fancy_batch.submit(2).expect("Render error");
         normal_batch.submit(1).expect("Render error");
@@ -75,11 +109,12 @@ impl GameState for UiState {
     }
 }
 impl UiState {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, run_mode: RunMode) -> Self {
         let config = Rc::from(RefCell::new(config));
         let snowflake_manager = Rc::new(RefCell::new(SnowflakeManager::new(Rc::clone(&config))));
         UiState {
             config: Rc::clone(&config),
+            run_mode,
             snowflake_manager: Rc::clone(&snowflake_manager),
             text_manager: FlakeCharLine::new(
                 PointF::from((1.0, 8.0)),
@@ -90,8 +125,11 @@ impl UiState {
             ),
             total_time: 0.0,
             solve_stream: None,
+            solve_runner: None,
             ui_text_manager: UiTextManager::new(config, snowflake_manager, Point::new(1, 5)),
+            log_manager: LogManager::new(),
             solve_state: SolveState::NotSolved,
+            solve_all_days: false,
             touch_mode: false,
         }
     }
@@ -168,9 +206,12 @@ impl UiState {
                     key: VirtualKeyCode::Back,
                     pressed: true,
                     ..
-                } => {
-                    self.text_manager.text.pop();
-                }
+                } => self.handle_backspace(),
+                BEvent::KeyboardInput {
+                    key: VirtualKeyCode::Tab,
+                    pressed: true,
+                    ..
+                } => self.log_manager.cycle_level(),
                 BEvent::KeyboardInput {
                     key: VirtualKeyCode::Return,
                     pressed: true,
@@ -208,9 +249,8 @@ impl UiState {
             .collect::<Vec<_>>();
         for key in js_unhandled_keys {
             match key.as_str() {
-                "Backspace" => {
-                    self.text_manager.text.pop();
-                }
+                "Backspace" => self.handle_backspace(),
+                "Tab" => self.log_manager.cycle_level(),
                 "Enter" => self.text_manager.text.clear(),
                 _ => {
                     let chars = key.chars().collect::<Vec<_>>();
@@ -237,6 +277,14 @@ impl UiState {
             }
         }
 
+        // Solve a day with the input submitted on the web page
+        if self.solve_stream.is_none() && !self.ui_text_manager.has_queued_animation() {
+            let request = JS_BRIDGE.lock().unwrap().custom_input_request.take();
+            if let Some((year_day, input)) = request {
+                self.start_solving(false, |runner| runner.run(year_day, Input::Custom(input)));
+            }
+        }
+
         if !self.touch_mode {
             let mouse_pos = INPUT.lock().mouse_pixel_pos();
             match mouse_pos {
@@ -246,22 +294,44 @@ impl UiState {
         }
     }
 
+    /// Cancels the running solutions, or deletes the last typed character if there is nothing to cancel.
+    fn handle_backspace(&mut self) {
+        match (&self.solve_state, &self.solve_runner) {
+            (SolveState::Solving, Some(runner)) => runner.cancel(),
+            _ => _ = self.text_manager.text.pop(),
+        }
+    }
+
     fn start_solving_solutions(&mut self) {
+        let days = create_list(&self.run_mode)
+            .into_iter()
+            .map(|s| YearDay::new(s.info.year, s.info.day))
+            .filter(|yd| yd.year == 2022)
+            .unique()
+            .sorted()
+            .collect_vec();
+        self.start_solving(true, |runner| runner.run_many(days));
+    }
+
+    fn start_solving(
+        &mut self,
+        solve_all_days: bool,
+        run: impl FnOnce(&dyn SolutionRunner<LocalSyncStream>) -> Arc<Mutex<LocalSyncStream>>,
+    ) {
         if self.solve_stream.is_some() || self.ui_text_manager.has_queued_animation() {
             return;
         }
+        let runner = match create_runner(&self.run_mode) {
+            Some(runner) => runner,
+            None => return,
+        };
 
         self.ui_text_manager.clear();
+        self.log_manager.clear();
         self.solve_state = SolveState::Solving;
-        let runner: Box<dyn SolutionRunner<LocalSyncStream>> =
-            match JS_BRIDGE.lock().unwrap().worker_wrapper {
-                Some(_) => Box::new(WasmRunner {}),
-                None => Box::new(ThreadSolutionRunner {}),
-            };
-        self.solve_stream = Some(runner.run(
-            YearDay { year: 2022, day: 1 },
-            aoc::core::solution_runner::Input::Default,
-        ));
+        self.solve_all_days = solve_all_days;
+        self.solve_stream = Some(run(runner.as_ref()));
+        self.solve_runner = Some(runner);
     }
 
     fn handle_window_resize(&mut self, _event: &BEvent, new_size: Point) {
@@ -305,40 +375,32 @@ impl UiState {
     }
 
     fn handle_solution_progress_updates(&mut self) {
-        let mut next_year: Option<YearDay> = None;
+        js_interop::check_worker_deadline();
         if let Some(x) = &self.solve_stream {
-            if let Some(items) = x.lock().unwrap().next_items() {
+            let next_items = x.lock().unwrap().next_items();
+            // The stream of a panicked WASM worker is closed without finishing all days
+            if next_items.is_none() && matches!(self.solve_state, SolveState::Solving) {
+                self.solve_state = SolveState::Solved;
+            }
+            if let Some(items) = next_items {
                 for item in items {
-                    if let SolveProgress::Done(pack) = &item {
-                        let map = solutions::create_map();
-                        let max_day = map.keys().max();
-                        if let Some(max_day) = max_day {
-                            if pack.year_day < *max_day {
-                                next_year =
-                                    Some(YearDay::new(pack.year_day.year, pack.year_day.day + 1));
-                            } else {
-                                self.solve_state = SolveState::Solved;
-                            }
+                    match &item {
+                        SolveProgress::Log(pack) => self.log_manager.add(pack),
+                        SolveProgress::Cancelled(_) => self.solve_state = SolveState::NotSolved,
+                        SolveProgress::Done(_) if !self.solve_all_days => {
+                            self.solve_state = SolveState::Solved
                         }
+                        SolveProgress::Summary(_) => self.solve_state = SolveState::Solved,
+                        _ => (),
                     }
                     self.ui_text_manager.update_progress(item);
                 }
             }
         }
 
-        if let SolveState::Solved = self.solve_state {
+        if let SolveState::Solved | SolveState::NotSolved = self.solve_state {
             self.solve_stream = None;
-        }
-
-        // Start next day if there are more days to solve
-        if let Some(next_day) = next_year {
-            let runner: Box<dyn SolutionRunner<LocalSyncStream>> =
-                match JS_BRIDGE.lock().unwrap().worker_wrapper {
-                    Some(_) => Box::new(WasmRunner {}),
-                    None => Box::new(ThreadSolutionRunner {}),
-                };
-            self.solve_stream =
-                Some(runner.run(next_day, aoc::core::solution_runner::Input::Default));
+            self.solve_runner = None;
         }
     }
 
@@ -351,3 +413,47 @@ impl UiState {
         }
     }
 }
+
+/// Creates a runner using the web worker if available, or a native thread otherwise.
+/// Returns None in WASM while the web worker is not ready.
+fn create_runner(run_mode: &RunMode) -> Option<Box<dyn SolutionRunner<LocalSyncStream>>> {
+    let options = SolveOptions {
+        log_level: LogLevel::Trace,
+        part_time_limit: Some(PART_TIME_LIMIT),
+        ..Default::default()
+    };
+    match run_mode {
+        RunMode::Replay(recording, speed) => Some(Box::new(ReplaySolutionRunner::new(
+            Arc::clone(recording),
+            *speed,
+        ))),
+        RunMode::Record(path) if !is_wasm() => Some(Box::new(RecordingSolutionRunner {
+            runner: Box::new(ThreadSolutionRunner { options }),
+            path: path.clone(),
+        })),
+        #[cfg(not(target_arch = "wasm32"))]
+        RunMode::HotReload(path) => Some(Box::new(HotReloadSolutionRunner {
+            options,
+            path: path.clone(),
+        })),
+        _ => match JS_BRIDGE.lock().unwrap().worker_wrapper {
+            Some(_) => Some(Box::new(WasmRunner {
+                part_time_limit: options.part_time_limit,
+            })),
+            None if is_wasm() => None,
+            None => Some(Box::new(ThreadSolutionRunner { options })),
+        },
+    }
+}
+
+/// Returns the solutions, taken from the hot reloaded library if it can be loaded.
+fn create_list(run_mode: &RunMode) -> Vec<SolutionType> {
+    match run_mode {
+        #[cfg(not(target_arch = "wasm32"))]
+        RunMode::HotReload(path) => match hot_reload::load(path) {
+            Ok(library) => library.create_list(),
+            Err(_) => solutions::create_list(),
+        },
+        _ => solutions::create_list(),
+    }
+}
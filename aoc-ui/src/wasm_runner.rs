@@ -1,32 +1,104 @@
-use crate::js_interop::{self, WorkerCommand};
+use crate::js_interop::{self, WorkerBatch, WorkerCommand};
 use aoc::{
-    core::solution_runner::{Input, LocalSyncStream, SolutionRunner, SyncStream},
+    core::solution_runner::{
+        Input, LocalSyncStream, ResultPack, SolutionRunner, SolveProgress, SyncStream,
+    },
     helpers::AsSome,
     util::YearDay,
 };
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use wasm_bindgen::JsValue;
 
-pub struct WasmRunner {}
+pub struct WasmRunner {
+    /// Time limit of each part, after which the worker is replaced, see [`js_interop::check_worker_deadline`].
+    pub part_time_limit: Option<Duration>,
+}
 impl SolutionRunner<LocalSyncStream> for WasmRunner {
     fn run(&self, day: YearDay, input: Input) -> Arc<Mutex<LocalSyncStream>> {
-        let stream = Arc::new(Mutex::new(LocalSyncStream::new()));
+        start_worker(
+            day,
+            WorkerCommand::StartDay(day, input),
+            self.part_time_limit,
+        )
+    }
+
+    fn run_many(&self, days: Vec<YearDay>) -> Arc<Mutex<LocalSyncStream>> {
+        let first_day = days.first().copied().unwrap_or_default();
+        start_worker(
+            first_day,
+            WorkerCommand::StartDays(days),
+            self.part_time_limit,
+        )
+    }
+
+    fn cancel(&self) {
         let mut js_bridge = js_interop::JS_BRIDGE.lock().unwrap();
 
-        if let Some(stream) = &mut js_bridge.worker_tx {
-            stream.lock().unwrap().close();
-        };
-        js_bridge.worker_tx = Some(Arc::clone(&stream));
-
-        // Send solve day command to js worker
-        js_bridge
-            .worker_wrapper
-            .as_some()
-            .post_message(&JsValue::from_str(
-                &serde_json::to_string(&WorkerCommand::StartDay(day, input)).unwrap(),
-            ))
-            .unwrap();
-
-        stream
+        // The worker is blocked by the running solution, so it can only be stopped by terminating it.
+        // Without a worker, a new one is being started after a panic, and must not resume the days.
+        let worker = js_bridge.worker_wrapper.take();
+        if let Some(worker) = &worker {
+            worker.terminate();
+        }
+        js_bridge.worker_batch = None;
+        js_bridge.worker_step = None;
+
+        if let (Some(stream), Some(year_day)) =
+            (js_bridge.worker_tx.take(), js_bridge.worker_year_day.take())
+        {
+            let mut stream = stream.lock().unwrap();
+            stream.send(SolveProgress::Cancelled(ResultPack {
+                year_day,
+                part: None,
+                value: (),
+                duration: Duration::default(),
+                verdict: None,
+            }));
+            stream.close();
+        }
+
+        drop(js_bridge);
+        if worker.is_some() {
+            js_interop::restart_worker();
+        }
     }
 }
+
+/// Sends the given command to the js worker, redirecting its progress to a new stream.
+fn start_worker(
+    first_day: YearDay,
+    command: WorkerCommand,
+    part_time_limit: Option<Duration>,
+) -> Arc<Mutex<LocalSyncStream>> {
+    let stream = Arc::new(Mutex::new(LocalSyncStream::new()));
+    let mut js_bridge = js_interop::JS_BRIDGE.lock().unwrap();
+
+    if let Some(stream) = &mut js_bridge.worker_tx {
+        stream.lock().unwrap().close();
+    };
+    js_bridge.worker_tx = Some(Arc::clone(&stream));
+    js_bridge.worker_year_day = Some(first_day);
+    js_bridge.worker_step = None;
+    js_bridge.worker_part_time_limit = part_time_limit;
+    js_bridge.worker_batch = match &command {
+        WorkerCommand::StartDay(..) => None,
+        WorkerCommand::StartDays(days) => Some(WorkerBatch {
+            remaining: days.clone(),
+            ..Default::default()
+        }),
+    };
+
+    // Send solve command to js worker
+    js_bridge
+        .worker_wrapper
+        .as_some()
+        .post_message(&JsValue::from_str(
+            &serde_json::to_string(&command).unwrap(),
+        ))
+        .unwrap();
+
+    stream
+}
@@ -82,15 +82,24 @@ function handleTouch(rust, event) {
  * @param {typeof import('./pkg')} rust
  */
 function registerKeyHandlers(rust) {
+    // Let the custom input form receive its own key events
+    const isFormEvent = event => event.target.closest && event.target.closest('form') !== null;
+
     document.addEventListener('keypress', event => {
+        if (isFormEvent(event)) {
+            return;
+        }
         var key = event.key;
         rust.push_key_event(key);
         event.preventDefault();
     }, false);
 
     document.addEventListener('keydown', (event) => {
+        if (isFormEvent(event)) {
+            return;
+        }
         var key = event.key;
-        if (key == 'Backspace') {
+        if (key == 'Backspace' || key == 'Tab') {
             rust.push_key_event(key);
             event.preventDefault();
         }
@@ -103,6 +112,41 @@ function registerKeyHandlers(rust) {
     canvas.addEventListener('touchcancel', event => handleTouch(rust, event));
 }
 
+/**
+ * Registers the form to solve a single day with a custom puzzle input.
+ * The input can be pasted, selected or dropped as a file.
+ * @param {typeof import('./pkg')} rust
+ */
+function registerCustomInput(rust) {
+    const form = document.getElementById('custom-input-form');
+    const year = document.getElementById('custom-input-year');
+    const day = document.getElementById('custom-input-day');
+    const file = document.getElementById('custom-input-file');
+    const text = document.getElementById('custom-input-text');
+
+    for (const solutionYear of rust.solution_years()) {
+        year.add(new Option(String(solutionYear)));
+    }
+
+    file.addEventListener('change', async () => {
+        if (file.files.length > 0) {
+            text.value = await file.files[0].text();
+        }
+    });
+    text.addEventListener('dragover', event => event.preventDefault());
+    text.addEventListener('drop', async event => {
+        const files = event.dataTransfer?.files;
+        if (files && files.length > 0) {
+            event.preventDefault();
+            text.value = await files[0].text();
+        }
+    });
+    form.addEventListener('submit', event => {
+        event.preventDefault();
+        rust.solve_custom_input(Number(year.value), Number(day.value), text.value);
+    });
+}
+
 async function initWorker() {
     const worker = new Worker(new URL('./worker.js', import.meta.url));
     await new Promise((resolve, reject) => {
@@ -123,6 +167,67 @@ async function initWorker() {
     return worker;
 }
 
+/**
+ * Stands in for the worker when the page is served by `aoc-cli serve --http`,
+ * solving natively on the local machine and streaming the progress as Server-Sent Events.
+ * Implements the part of the Worker interface used by the rust code.
+ */
+class ServerWorker {
+    constructor() {
+        this.onmessage = null;
+        this.runId = null;
+        this.events = null;
+    }
+
+    /** @param {string} command serialized WorkerCommand */
+    postMessage(command) {
+        fetch('api/runs', { method: 'POST', body: command })
+            .then(response => response.json())
+            .then(({ id }) => {
+                this.runId = id;
+                this.events = new EventSource(`api/runs/${id}/events`);
+                this.events.onmessage = event => this.onmessage?.(event);
+                this.events.addEventListener('end', () => this.events.close());
+            })
+            .catch(onError);
+    }
+
+    terminate() {
+        this.events?.close();
+        if (this.runId !== null) {
+            fetch(`api/runs/${this.runId}/cancel`, { method: 'POST' });
+        }
+    }
+}
+
+/** Returns whether the page is served by `aoc-cli serve --http`. */
+async function isServedByCli() {
+    try {
+        const response = await fetch('api/solutions');
+        return response.ok && response.headers.get('Content-Type') === 'application/json';
+    } catch {
+        return false;
+    }
+}
+
+/**
+ * Creates a new worker and registers it in the rust code.
+ * @param {typeof import('./pkg')} rust
+ * @param {boolean} useServer
+ */
+async function startWorker(rust, useServer) {
+    let worker = useServer ? new ServerWorker() : await initWorker();
+    rust.set_worker(worker);
+    worker.onmessage = ({ data }) => {
+        console.log("main received: ", data);
+        if (data === 'panicked') {
+            rust.on_worker_panicked();
+        } else {
+            rust.on_worker_message(data);
+        }
+    };
+}
+
 async function start() {
     try {
         /** @type {Promise<typeof import('./pkg')>} */
@@ -133,13 +238,12 @@ async function start() {
         window.addEventListener('resize', () => onResize(rustMain));
         onResize(rustMain);
         registerKeyHandlers(rustMain);
+        registerCustomInput(rustMain);
 
-        let worker = await initWorker();
-        rustMain.set_worker(worker);
-        worker.onmessage = ({ data }) => {
-            console.log("main received: ", data);
-            rustMain.on_worker_message(data);
-        };
+        // The worker is terminated when a running solution is cancelled, so rust needs a way to replace it.
+        const useServer = await isServedByCli();
+        rustMain.set_restart_worker(() => startWorker(rustMain, useServer).catch(onError));
+        await startWorker(rustMain, useServer);
     } catch (err) {
         onError(err);
     }
@@ -9,6 +9,8 @@ async function init() {
             rustWorker.worker_on_message(data);
         } catch (err) {
             console.error(err);
+            // A panic aborts the rust code, leaving the worker unusable
+            self.postMessage('panicked');
         }
     };
     self.postMessage('initialized');